@@ -0,0 +1,85 @@
+// Validates schemas/command_tree.json at compile time so a bad regeneration fails the
+// build instead of panicking at startup.
+
+#[path = "src/command_tree.rs"]
+#[allow(dead_code)]
+mod command_tree;
+
+use std::collections::HashSet;
+
+const TREE_PATH: &str = "schemas/command_tree.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={TREE_PATH}");
+    println!("cargo:rerun-if-changed=src/command_tree.rs");
+
+    let raw = std::fs::read_to_string(TREE_PATH).unwrap_or_else(|err| fail(&format!("{err}")));
+    let tree: command_tree::CommandTree =
+        serde_json::from_str(&raw).unwrap_or_else(|err| fail(&format!("invalid schema: {err}")));
+
+    let mut problems = Vec::new();
+    if !tree.base_path.starts_with('/') {
+        problems.push(format!(
+            "base_path {:?} must start with '/'",
+            tree.base_path
+        ));
+    }
+    for res in &tree.resources {
+        let mut seen = HashSet::new();
+        for op in &res.ops {
+            if !seen.insert(op.name.as_str()) {
+                problems.push(format!("duplicate op {} {}", res.name, op.name));
+            }
+            let bounds = [&op.min_api_version, &op.max_api_version].map(|raw| {
+                raw.as_deref().map(|raw| {
+                    command_tree::parse_version(raw).unwrap_or_else(|| {
+                        problems.push(format!("{} {}: bad api version {raw:?}", res.name, op.name));
+                        Vec::new()
+                    })
+                })
+            });
+            if let [Some(min), Some(max)] = &bounds
+                && min > max
+            {
+                problems.push(format!(
+                    "{} {}: min_api_version is above max_api_version",
+                    res.name, op.name
+                ));
+            }
+            let params: HashSet<&str> = op.params.iter().map(|p| p.name.as_str()).collect();
+            let tokens = path_tokens(&op.path);
+            for token in &tokens {
+                if !params.contains(token) {
+                    problems.push(format!(
+                        "{} {}: path token <{token}> has no param",
+                        res.name, op.name
+                    ));
+                }
+            }
+            for param in &params {
+                if !tokens.contains(param) {
+                    problems.push(format!(
+                        "{} {}: param {param} not in path {}",
+                        res.name, op.name, op.path
+                    ));
+                }
+            }
+        }
+    }
+    if !problems.is_empty() {
+        fail(&problems.join("\n  "));
+    }
+}
+
+/// Param names from `<converter:name>`, `<name>`, and `:name` tokens.
+fn path_tokens(path: &str) -> Vec<&str> {
+    command_tree::path_tokens(path)
+        .unwrap_or_else(|| fail(&format!("unclosed '<' in path {path}")))
+        .into_iter()
+        .map(|token| token.name)
+        .collect()
+}
+
+fn fail(message: &str) -> ! {
+    panic!("{TREE_PATH}:\n  {message}");
+}
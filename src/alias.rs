@@ -0,0 +1,105 @@
+use anyhow::{Context, Result, anyhow};
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+/// Alias name -> argument list, loaded from `aliases.toml`.
+///
+/// ```toml
+/// open-issues = ["work-item", "list", "--project-id", "$1", "--per-page", "100"]
+/// me = "user me-list"
+/// ```
+pub type Aliases = BTreeMap<String, Vec<String>>;
+
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("plane"));
+    }
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("plane"))
+}
+
+pub fn aliases_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("aliases.toml"))
+}
+
+pub fn load() -> Result<Aliases> {
+    let Some(path) = aliases_path() else {
+        return Ok(Aliases::new());
+    };
+    if !path.exists() {
+        return Ok(Aliases::new());
+    }
+    let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    parse(&raw).with_context(|| format!("parse {}", path.display()))
+}
+
+pub fn parse(raw: &str) -> Result<Aliases> {
+    let table: toml::Table = raw.parse()?;
+    let mut aliases = Aliases::new();
+    for (name, value) in table {
+        let args = match value {
+            toml::Value::String(line) => line.split_whitespace().map(str::to_string).collect(),
+            toml::Value::Array(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    toml::Value::String(arg) => Ok(arg),
+                    other => Err(anyhow!("alias {name}: expected string, got {other}")),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            other => {
+                return Err(anyhow!(
+                    "alias {name}: expected string or array, got {other}"
+                ));
+            }
+        };
+        aliases.insert(name, args);
+    }
+    Ok(aliases)
+}
+
+/// Rewrites `plane :NAME args...` and `plane alias run NAME args...` into the alias
+/// expansion. Other argv shapes are returned untouched.
+pub fn expand_argv(argv: Vec<String>) -> Result<Vec<String>> {
+    let (name, rest_idx) = match argv.get(1).map(String::as_str) {
+        Some(arg) if arg.len() > 1 && arg.starts_with(':') => (arg[1..].to_string(), 2),
+        Some("alias") if argv.get(2).map(String::as_str) == Some("run") => {
+            let name = argv
+                .get(3)
+                .cloned()
+                .ok_or_else(|| anyhow!("alias name required"))?;
+            (name, 4)
+        }
+        _ => return Ok(argv),
+    };
+
+    let aliases = load()?;
+    let template = aliases
+        .get(&name)
+        .ok_or_else(|| anyhow!("unknown alias {name}"))?;
+    let mut out = vec![argv[0].clone()];
+    out.extend(substitute(template, &argv[rest_idx..])?);
+    Ok(out)
+}
+
+/// Replaces `$N` with the Nth argument; arguments not referenced are appended.
+pub fn substitute(template: &[String], args: &[String]) -> Result<Vec<String>> {
+    let mut used = vec![false; args.len()];
+    let mut out = Vec::with_capacity(template.len() + args.len());
+    for part in template {
+        match part.strip_prefix('$').and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if n >= 1 => {
+                let value = args
+                    .get(n - 1)
+                    .ok_or_else(|| anyhow!("alias expects argument ${n}"))?;
+                used[n - 1] = true;
+                out.push(value.clone());
+            }
+            _ => out.push(part.clone()),
+        }
+    }
+    out.extend(
+        args.iter()
+            .zip(used)
+            .filter(|(_, used)| !used)
+            .map(|(arg, _)| arg.clone()),
+    );
+    Ok(out)
+}
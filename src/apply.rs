@@ -0,0 +1,174 @@
+use crate::command_tree::CommandTree;
+use crate::http::ensure_success;
+use crate::{
+    build_client_with_key, build_path, find_op, join_url, param_default, resolve_api_base,
+    resolve_api_key, workspace_flag, write_stdout_line,
+};
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One file resolved to an operation: `_op` picks it, `_params` fills path params,
+/// and the remaining keys are the request body.
+#[derive(Debug)]
+pub struct Planned {
+    pub op: String,
+    pub method: String,
+    pub path: String,
+    pub body: Value,
+}
+
+pub fn plan(
+    tree: &CommandTree,
+    doc: Value,
+    default_op: Option<&str>,
+    include_deprecated: bool,
+    workspace: Option<&str>,
+) -> Result<Planned> {
+    let Value::Object(mut body) = doc else {
+        return Err(anyhow!("expected a JSON object"));
+    };
+    let op_spec = match body.remove("_op") {
+        Some(Value::String(spec)) => spec,
+        Some(other) => return Err(anyhow!("_op must be a string, got {other}")),
+        None => default_op
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("no _op key and no --op given"))?,
+    };
+    let (res_name, op_name) = op_spec
+        .split_once(char::is_whitespace)
+        .map(|(res, op)| (res.trim(), op.trim()))
+        .ok_or_else(|| anyhow!("invalid op {op_spec:?}; expected \"RESOURCE OP\""))?;
+    let op = find_op(tree, res_name, op_name)?;
+    if op.deprecated && !include_deprecated {
+        return Err(anyhow!(
+            "deprecated endpoint {op_spec}; re-run with --include-deprecated"
+        ));
+    }
+
+    let given = match body.remove("_params") {
+        Some(Value::Object(map)) => map,
+        Some(other) => return Err(anyhow!("_params must be an object, got {other}")),
+        None => Default::default(),
+    };
+    let mut params = HashMap::new();
+    for param in &op.params {
+        let value = match given.get(&param.name).or_else(|| given.get(&param.flag)) {
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(other) => Some(other.to_string()),
+            None => param_default(param, workspace),
+        };
+        let value = value.ok_or_else(|| anyhow!("missing _params.{}", param.name))?;
+        params.insert(param.name.clone(), value);
+    }
+
+    Ok(Planned {
+        op: format!("{res_name} {op_name}"),
+        method: op.method.clone(),
+        path: build_path(op, &params)?,
+        body: Value::Object(body),
+    })
+}
+
+/// Files for each `-f` value: a file, every `*.json` in a directory, or a glob.
+pub fn expand_inputs(inputs: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)
+                .with_context(|| format!("read {input}"))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else if input.contains(['*', '?', '[']) {
+            let mut matched: Vec<PathBuf> = glob::glob(input)
+                .with_context(|| format!("invalid glob {input}"))?
+                .filter_map(|entry| entry.ok())
+                .collect();
+            if matched.is_empty() {
+                return Err(anyhow!("no files match {input}"));
+            }
+            matched.sort();
+            files.extend(matched);
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+/// Sends every file in order, continuing past failures unless `--fail-fast` is set.
+pub fn run(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let inputs: Vec<String> = matches
+        .get_many::<String>("file")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let files = expand_inputs(&inputs)?;
+    let default_op = matches.get_one::<String>("op").map(String::as_str);
+    let include_deprecated = matches.get_flag("include-deprecated");
+    let workspace = workspace_flag(matches);
+    let dry_run = matches.get_flag("dry-run");
+    let fail_fast = matches.get_flag("fail-fast");
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+    // Looked up once so --api-key-command runs once per apply, not per file; a dry run needs none.
+    let key = if dry_run {
+        String::new()
+    } else {
+        resolve_api_key(matches)?
+    };
+
+    let (mut ok, mut failed) = (0, 0);
+    for file in &files {
+        let label = file.display();
+        let result = fs::read_to_string(file)
+            .with_context(|| format!("read {label}"))
+            .and_then(|raw| serde_json::from_str(&raw).context("invalid JSON"))
+            .and_then(|doc| plan(tree, doc, default_op, include_deprecated, workspace))
+            .and_then(|planned| {
+                let url = join_url(&api_url, &base_path, &planned.path);
+                if dry_run {
+                    return Ok(format!(
+                        "plan {label}: {} -> {} {url}",
+                        planned.op, planned.method
+                    ));
+                }
+                let client = build_client_with_key(matches, &planned.method, key.clone())?;
+                let response = client.execute(&planned.method, &url, &[], Some(planned.body))?;
+                ensure_success(response.status, &url, &response.body)?;
+                let id = response
+                    .body
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(|id| format!(" (id {id})"))
+                    .unwrap_or_default();
+                Ok(format!(
+                    "ok   {label}: {} {} -> http {}{id}",
+                    planned.method, url, response.status
+                ))
+            });
+        match result {
+            Ok(line) => {
+                ok += 1;
+                write_stdout_line(&line)?;
+            }
+            Err(err) => {
+                failed += 1;
+                write_stdout_line(&format!("FAIL {label}: {err:#}"))?;
+                if fail_fast {
+                    break;
+                }
+            }
+        }
+    }
+
+    write_stdout_line(&format!("{ok} ok, {failed} failed"))?;
+    if failed > 0 {
+        return Err(anyhow!("apply failed for {failed} file(s)"));
+    }
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use crate::command_tree::{Operation, Param};
+use crate::http::{self, Timing, ensure_success};
+use crate::{
+    build_client_with_key, build_path, join_url, pacer, param_default, progress_bar,
+    progress_style, resolve_api_key, workspace_flag, write_stdout_line,
+};
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+/// The path param each `--batch` id is substituted into: the op's last one.
+pub fn id_param(op: &Operation) -> Option<&Param> {
+    op.method
+        .eq_ignore_ascii_case("DELETE")
+        .then(|| op.params.last())
+        .flatten()
+}
+
+/// One id per line; blank lines and `#` comments are skipped.
+pub fn parse_ids(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn read_ids(source: &str) -> Result<Vec<String>> {
+    let raw = if source == "-" {
+        let mut raw = String::new();
+        std::io::stdin()
+            .read_to_string(&mut raw)
+            .context("read ids from stdin")?;
+        raw
+    } else {
+        fs::read_to_string(source).with_context(|| format!("read {source}"))?
+    };
+    Ok(parse_ids(&raw))
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow!(
+            "{prompt}: stdin is not a terminal; pass --yes to confirm"
+        ));
+    }
+    eprint!("{prompt} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// `--batch`: deletes every listed id with up to `--concurrency` requests in flight,
+/// reporting each result and a final tally.
+pub fn run(
+    matches: &clap::ArgMatches,
+    op_matches: &clap::ArgMatches,
+    op: &Operation,
+    api_url: &str,
+    base_path: &str,
+    source: &str,
+) -> Result<()> {
+    let id_param = id_param(op).ok_or_else(|| {
+        anyhow!(
+            "--batch supports DELETE operations with a path id, not {} {}",
+            op.method,
+            op.path
+        )
+    })?;
+    let mut params = HashMap::new();
+    for param in op.params.iter().filter(|param| param.name != id_param.name) {
+        let value = op_matches
+            .get_one::<String>(&param.name)
+            .cloned()
+            .or_else(|| param_default(param, workspace_flag(op_matches)))
+            .ok_or_else(|| anyhow!("missing required argument --{}", param.flag))?;
+        params.insert(param.name.clone(), value);
+    }
+    let ids = read_ids(source)?;
+    if ids.is_empty() {
+        return Err(anyhow!("no ids in {source}"));
+    }
+    let urls = ids
+        .iter()
+        .map(|id| {
+            let mut params = params.clone();
+            params.insert(id_param.name.clone(), id.clone());
+            Ok(join_url(api_url, base_path, &build_path(op, &params)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if matches.get_flag("dry-run") {
+        for url in &urls {
+            write_stdout_line(&format!("DELETE {url}"))?;
+        }
+        return Ok(());
+    }
+    if !matches.get_flag("yes")
+        && !confirm(&format!("delete {} item(s) via {}", ids.len(), op.path))?
+    {
+        return Err(anyhow!("aborted"));
+    }
+
+    crate::interrupt::install();
+    let key = resolve_api_key(matches)?;
+    let workers = (*matches.get_one::<usize>("concurrency").unwrap_or(&4)).clamp(1, ids.len());
+    let fail_fast = matches.get_flag("fail-fast");
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let failed = AtomicUsize::new(0);
+    let report = Mutex::new(());
+    let pacer = pacer(matches);
+    let bar = progress_bar(matches);
+    if let Some(bar) = &bar {
+        bar.set_length(urls.len() as u64);
+        bar.set_style(progress_style("{spinner} deleted {pos}/{len} ids ({msg})"));
+    }
+    let timings = thread::scope(|scope| -> Result<Vec<Timing>> {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| -> Result<Vec<Timing>> {
+                    let client = build_client_with_key(matches, "DELETE", key.clone())?
+                        .with_pacer(pacer.clone());
+                    while !stop.load(Ordering::SeqCst) && !crate::interrupt::requested() {
+                        let idx = next.fetch_add(1, Ordering::SeqCst);
+                        if idx >= urls.len() {
+                            break;
+                        }
+                        let (id, url) = (&ids[idx], &urls[idx]);
+                        let line =
+                            match client
+                                .execute("DELETE", url, &[], None)
+                                .and_then(|response| {
+                                    ensure_success(response.status, url, &response.body)?;
+                                    Ok(response.status)
+                                }) {
+                                Ok(status) => format!("ok   {id}: http {status}"),
+                                Err(err) => {
+                                    failed.fetch_add(1, Ordering::SeqCst);
+                                    if fail_fast {
+                                        stop.store(true, Ordering::SeqCst);
+                                    }
+                                    format!("FAIL {id}: {err:#}")
+                                }
+                            };
+                        let _guard = report.lock().unwrap_or_else(|err| err.into_inner());
+                        write_stdout_line(&line)?;
+                        if let Some(bar) = &bar {
+                            bar.inc(1);
+                            bar.set_message(format!("{} failed", failed.load(Ordering::SeqCst)));
+                        }
+                    }
+                    Ok(client.timings())
+                })
+            })
+            .collect();
+        let mut timings = Vec::new();
+        for handle in handles {
+            timings.extend(
+                handle
+                    .join()
+                    .map_err(|_| anyhow!("batch worker panicked"))??,
+            );
+        }
+        Ok(timings)
+    });
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+    let timings = timings?;
+
+    let failed = failed.into_inner();
+    let attempted = next.into_inner().min(urls.len());
+    write_stdout_line(&format!(
+        "{} deleted, {failed} failed, {} skipped",
+        attempted - failed,
+        urls.len() - attempted
+    ))?;
+    if let Some(summary) = http::summarize_timings(&timings) {
+        eprintln!("{summary}");
+    }
+    crate::interrupt::check()?;
+    if failed > 0 {
+        return Err(anyhow!("batch delete failed for {failed} id(s)"));
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+
+#[cfg(feature = "clipboard")]
+pub fn copy(text: &str) -> Result<()> {
+    use anyhow::Context;
+    let mut clipboard = arboard::Clipboard::new().context("open clipboard")?;
+    clipboard
+        .set_text(text.to_string())
+        .context("copy to clipboard")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: &str) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "built without clipboard support; rebuild with --features clipboard"
+    ))
+}
@@ -23,6 +23,44 @@ pub struct Operation {
     pub path: String,
     pub deprecated: bool,
     pub params: Vec<Param>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<QueryParam>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
+    /// `[["per_page", "100"], ...]` sent unless the same key is given explicitly.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_query: Vec<(String, String)>,
+    /// `true` forces a trailing slash, `false` strips it; unset keeps the template's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_slash: Option<bool>,
+    /// `Accept` sent unless `--accept` is given, e.g. `text/csv` for export-style ops.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_accept: Option<String>,
+    /// Oldest Plane API version (`1.2`, `0.24.1`) serving this operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_api_version: Option<String>,
+    /// Newest Plane API version serving this operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_api_version: Option<String>,
+    /// Relations `--expand` accepts; unset means expands are not checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expand: Vec<String>,
+}
+
+impl Operation {
+    /// Ops whose default `Accept` is not JSON get `--download` to save the raw body.
+    pub fn downloads(&self) -> bool {
+        self.default_accept
+            .as_deref()
+            .is_some_and(|accept| !accept.contains("json"))
+    }
+
+    /// Whether `version` falls in `min_api_version..=max_api_version` (open ends allowed).
+    pub fn available_on(&self, version: &[u64]) -> bool {
+        let bound = |raw: &Option<String>| raw.as_deref().and_then(parse_version);
+        bound(&self.min_api_version).is_none_or(|min| version >= min.as_slice())
+            && bound(&self.max_api_version).is_none_or(|max| version <= max.as_slice())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -32,6 +70,102 @@ pub struct Param {
     pub flag: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
+pub struct QueryParam {
+    pub name: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+impl QueryParam {
+    pub fn is_bool(&self) -> bool {
+        matches!(self.kind.as_deref(), Some("bool" | "boolean"))
+    }
+
+    /// CLI flag for typed query params, e.g. `is_active` -> `is-active`.
+    pub fn flag(&self) -> String {
+        self.name.replace('_', "-")
+    }
+}
+
+/// A path parameter in a template: Django-style `<converter:name>` / `<name>`, or a
+/// whole `:name` segment (FastAPI/Starlette style). `start..end` is the token's span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathToken<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub name: &'a str,
+    pub converter: Option<&'a str>,
+}
+
+/// Tokens in template order; `None` if a `<` is never closed.
+pub fn path_tokens(template: &str) -> Option<Vec<PathToken<'_>>> {
+    let bytes = template.as_bytes();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'<' => {
+                let end = idx + 1 + template[idx + 1..].find('>')?;
+                let token = &template[idx + 1..end];
+                let (converter, name) = match token.rsplit_once(':') {
+                    Some((converter, name)) => (Some(converter), name),
+                    None => (None, token),
+                };
+                tokens.push(PathToken {
+                    start: idx,
+                    end: end + 1,
+                    name,
+                    converter,
+                });
+                idx = end + 1;
+            }
+            b':' if idx == 0 || bytes[idx - 1] == b'/' => {
+                let end = template[idx..]
+                    .find('/')
+                    .map_or(template.len(), |len| idx + len);
+                if end > idx + 1 {
+                    tokens.push(PathToken {
+                        start: idx,
+                        end,
+                        name: &template[idx + 1..end],
+                        converter: None,
+                    });
+                }
+                idx = end;
+            }
+            _ => idx += 1,
+        }
+    }
+    Some(tokens)
+}
+
+/// Dotted numeric version (`1.2`, `v0.24.1`); trailing `.0`s are ignored so `1.2 == 1.2.0`.
+pub fn parse_version(raw: &str) -> Option<Vec<u64>> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix('v').unwrap_or(raw);
+    let mut parts = raw
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
+
+impl CommandTree {
+    /// Drops operations unavailable on `version`, and resources left empty.
+    pub fn retain_api_version(&mut self, version: &[u64]) {
+        for res in &mut self.resources {
+            res.ops.retain(|op| op.available_on(version));
+        }
+        self.resources.retain(|res| !res.ops.is_empty());
+    }
+}
+
+/// The schema, op-name uniqueness, and path/param agreement are checked by build.rs.
 pub fn load_command_tree() -> CommandTree {
     let raw = include_str!("../schemas/command_tree.json");
     serde_json::from_str(raw).expect("invalid command_tree.json")
@@ -0,0 +1,208 @@
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// `config.toml` next to `aliases.toml`.
+///
+/// ```toml
+/// [headers]                 # sent with every request
+/// "Accept-Language" = "en"
+///
+/// [fields]                  # --fields @summary
+/// summary = "id,name,state"
+///
+/// [profiles.base]
+/// base_url = "https://plane.example.com/api/v1"
+///
+/// [profiles.staging]          # --profile staging: base's settings, then its own
+/// inherits = "base"
+/// base_url = "https://staging.plane.example.com/api/v1"
+///
+/// [profiles.staging.headers]  # added (and overriding) for --profile staging
+/// "X-Tenant" = "acme-staging"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Named field sets used as `--fields @name` / `--select @name`.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Parent profile whose settings this one extends and overrides.
+    #[serde(default)]
+    pub inherits: Option<String>,
+    /// Like `PLANE_BASE_URL`; the env vars and `--api-url`/`--base-path` still win.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// A profile with its `inherits` chain applied.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Resolved {
+    /// The profile first, then each ancestor.
+    pub chain: Vec<String>,
+    pub base_url: Option<String>,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Config {
+    /// `profile`, then the profiles it inherits from. `parse` has already rejected
+    /// unknown parents and cycles. Undefined profiles (keyring-only) chain to themselves.
+    pub fn chain<'a>(&'a self, profile: &'a str) -> Vec<&'a str> {
+        let mut chain = vec![profile];
+        while let Some(parent) = self
+            .profiles
+            .get(chain[chain.len() - 1])
+            .and_then(|p| p.inherits.as_deref())
+        {
+            if chain.contains(&parent) {
+                break;
+            }
+            chain.push(parent);
+        }
+        chain
+    }
+
+    /// Top-level headers, then each profile's from the root ancestor down, later
+    /// names replacing earlier ones case-insensitively.
+    pub fn headers(&self, profile: &str) -> Vec<(String, String)> {
+        self.resolve(profile).headers
+    }
+
+    pub fn resolve(&self, profile: &str) -> Resolved {
+        let chain = self.chain(profile);
+        let profiles: Vec<&Profile> = chain
+            .iter()
+            .filter_map(|name| self.profiles.get(*name))
+            .collect();
+        let mut headers = Vec::new();
+        let inherited = profiles.iter().rev().flat_map(|p| &p.headers);
+        for (name, value) in self.headers.iter().chain(inherited) {
+            set_header(&mut headers, name, value);
+        }
+        Resolved {
+            chain: chain.iter().map(|name| name.to_string()).collect(),
+            base_url: profiles.iter().find_map(|p| p.base_url.clone()),
+            headers,
+        }
+    }
+
+    fn check_inheritance(&self) -> Result<()> {
+        for (name, profile) in &self.profiles {
+            let mut seen = vec![name.as_str()];
+            let mut parent = profile.inherits.as_deref();
+            while let Some(current) = parent {
+                if seen.contains(&current) {
+                    seen.push(current);
+                    return Err(anyhow!("profile inheritance cycle: {}", seen.join(" -> ")));
+                }
+                let next = self.profiles.get(current).ok_or_else(|| {
+                    anyhow!(
+                        "profile {} inherits unknown profile {current}",
+                        seen[seen.len() - 1]
+                    )
+                })?;
+                seen.push(current);
+                parent = next.inherits.as_deref();
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    crate::alias::config_dir().map(|dir| dir.join("config.toml"))
+}
+
+pub fn load() -> Result<Config> {
+    let Some(path) = config_path() else {
+        return Ok(Config::default());
+    };
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    parse(&raw).with_context(|| format!("parse {}", path.display()))
+}
+
+pub fn parse(raw: &str) -> Result<Config> {
+    let config: Config = toml::from_str(raw)?;
+    config.check_inheritance()?;
+    Ok(config)
+}
+
+/// Replaces an existing header of the same name (case-insensitive) or appends.
+pub fn set_header(headers: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match headers
+        .iter_mut()
+        .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+    {
+        Some(entry) => entry.1 = value.to_string(),
+        None => headers.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// Parses `Name: Value` (as given to `--header`).
+pub fn parse_header(input: &str) -> Result<(String, String)> {
+    let (name, value) = input
+        .split_once(':')
+        .ok_or_else(|| anyhow!("invalid header {input:?}; expected \"Name: Value\""))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("invalid header {input:?}; empty name"));
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// `--header-file`: `Name: Value` per line; blank lines and `#` comments are skipped.
+pub fn parse_header_lines(raw: &str) -> Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    for (idx, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        headers.push(parse_header(line).with_context(|| format!("line {}", idx + 1))?);
+    }
+    Ok(headers)
+}
+
+/// Headers whose values must not be echoed (dry-run output, diagnostics).
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    [
+        "authorization",
+        "cookie",
+        "token",
+        "secret",
+        "password",
+        "api-key",
+        "apikey",
+    ]
+    .iter()
+    .any(|needle| name.contains(needle))
+}
+
+pub fn redact_headers(headers: &[(String, String)]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive_header(name) {
+                "<redacted>".to_string()
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
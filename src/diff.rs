@@ -0,0 +1,128 @@
+use crate::http::ensure_success;
+use crate::{Target, build_client, use_color, write_stdout_line};
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+/// Field-level changes the local body would make to `remote`. Objects are compared
+/// key by key (dotted paths); other values as a whole. A `null` in the body removes
+/// the field. With `replace` (PUT), remote fields missing from the body are removed.
+pub fn diff(remote: &Value, local: &Value, replace: bool) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_into(&mut changes, "", remote, local, replace);
+    changes
+}
+
+fn diff_into(
+    changes: &mut Vec<Change>,
+    prefix: &str,
+    remote: &Value,
+    local: &Value,
+    replace: bool,
+) {
+    let (Value::Object(remote), Value::Object(local)) = (remote, local) else {
+        if remote != local {
+            changes.push(Change::Changed {
+                path: prefix.to_string(),
+                from: remote.clone(),
+                to: local.clone(),
+            });
+        }
+        return;
+    };
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    for (key, value) in local {
+        match (remote.get(key), value) {
+            (None | Some(Value::Null), Value::Null) => {}
+            (Some(old), Value::Null) => changes.push(Change::Removed {
+                path: join(key),
+                value: old.clone(),
+            }),
+            (None, value) => changes.push(Change::Added {
+                path: join(key),
+                value: value.clone(),
+            }),
+            (Some(old), value) => diff_into(changes, &join(key), old, value, replace),
+        }
+    }
+    if replace {
+        for (key, value) in remote {
+            if !local.contains_key(key) && !value.is_null() {
+                changes.push(Change::Removed {
+                    path: join(key),
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+}
+
+pub fn render(changes: &[Change], color: bool) -> String {
+    let paint = |code: &str, line: String| {
+        if color {
+            format!("\x1b[{code}m{line}\x1b[0m")
+        } else {
+            line
+        }
+    };
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::Added { path, value } => paint("32", format!("+ {path}: {value}")),
+            Change::Removed { path, value } => paint("31", format!("- {path}: {value}")),
+            Change::Changed { path, from, to } => paint("33", format!("~ {path}: {from} -> {to}")),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `--diff`: GETs the target URL and prints what the PUT/PATCH body would change,
+/// without sending it.
+pub fn run(matches: &clap::ArgMatches, target: &Target, body: Option<Value>) -> Result<()> {
+    let replace = match target.method.to_ascii_uppercase().as_str() {
+        "PUT" => true,
+        "PATCH" => false,
+        other => {
+            return Err(anyhow!(
+                "--diff needs a PUT or PATCH operation, not {other}"
+            ));
+        }
+    };
+    let local =
+        body.ok_or_else(|| anyhow!("--diff needs a request body (--body-json/--body-file)"))?;
+    let response = build_client(matches, "GET")?.execute("GET", target.url, &[], None)?;
+    ensure_success(response.status, target.url, &response.body)?;
+
+    let changes = diff(&response.body, &local, replace);
+    if changes.is_empty() {
+        write_stdout_line("no changes")?;
+        return Ok(());
+    }
+    write_stdout_line(&render(&changes, use_color(matches)))?;
+    if matches.get_flag("exit-on-diff") {
+        return Err(anyhow!("{} field(s) differ", changes.len()));
+    }
+    Ok(())
+}
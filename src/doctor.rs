@@ -0,0 +1,157 @@
+use crate::command_tree::CommandTree;
+use crate::{
+    build_client_with_key, find_op, join_url, profile_name, resolve_api_base, resolve_api_key,
+    write_stdout_line,
+};
+use anyhow::{Result, anyhow};
+
+struct Check {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+    hint: &'static str,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            ok: true,
+            detail: detail.into(),
+            hint: "",
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: &'static str) -> Self {
+        Self {
+            name,
+            ok: false,
+            detail: detail.into(),
+            hint,
+        }
+    }
+}
+
+/// Runs configuration checks in order; later checks are skipped once a prerequisite fails.
+pub fn run(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let mut checks = Vec::new();
+    write_stdout_line(&format!("profile: {}", profile_name(matches)))?;
+
+    let key = match resolve_api_key(matches) {
+        Ok(key) => {
+            checks.push(Check::pass(
+                "api key",
+                format!("found ({} chars)", key.len()),
+            ));
+            Some(key)
+        }
+        Err(err) => {
+            checks.push(Check::fail(
+                "api key",
+                err.to_string(),
+                "export PLANE_API_KEY, pass --api-key*, or run `plane login`",
+            ));
+            None
+        }
+    };
+
+    let base = match resolve_api_base(tree, matches).and_then(|(api_url, base_path)| {
+        let parsed = reqwest::Url::parse(&api_url).map_err(|err| anyhow!("{api_url}: {err}"))?;
+        if parsed.host_str().is_none() {
+            return Err(anyhow!("{api_url} has no host"));
+        }
+        Ok((api_url, base_path))
+    }) {
+        Ok((api_url, base_path)) => {
+            checks.push(Check::pass(
+                "base url",
+                format!("{api_url} (path {base_path})"),
+            ));
+            Some((api_url, base_path))
+        }
+        Err(err) => {
+            checks.push(Check::fail(
+                "base url",
+                err.to_string(),
+                "set PLANE_BASE_URL=https://host/api/v1",
+            ));
+            None
+        }
+    };
+
+    let reachable = match &base {
+        // Same client as real commands: timeouts, HTTP version, headers, user agent.
+        Some((api_url, _)) => {
+            match build_client_with_key(matches, "GET", key.clone().unwrap_or_default())
+                .and_then(|client| client.execute("GET", api_url, &[], None))
+            {
+                Ok(resp) => {
+                    checks.push(Check::pass(
+                        "connectivity",
+                        format!("{api_url} answered http {}", resp.status),
+                    ));
+                    true
+                }
+                Err(err) => {
+                    checks.push(Check::fail(
+                        "connectivity",
+                        format!("{api_url}: {err:#}"),
+                        "check DNS, proxy/VPN, and TLS certificates for the host",
+                    ));
+                    false
+                }
+            }
+        }
+        None => false,
+    };
+
+    if let (Some((api_url, base_path)), Some(key), true) = (&base, &key, reachable) {
+        let path = find_op(tree, "user", "me-list")
+            .map(|op| op.path.clone())
+            .unwrap_or_else(|_| "users/me/".to_string());
+        let url = join_url(api_url, base_path, &path);
+        let check = match build_client_with_key(matches, "GET", key.clone()) {
+            Err(err) => Check::fail(
+                "auth",
+                format!("{err:#}"),
+                "check the client flags (--header, timeouts, TLS options)",
+            ),
+            Ok(client) => match client.execute("GET", &url, &[], None) {
+                Ok(resp) if (200..300).contains(&resp.status) => Check::pass("auth", url.clone()),
+                Ok(resp) if matches!(resp.status, 401 | 403) => Check::fail(
+                    "auth",
+                    format!("http {} from {url}", resp.status),
+                    "API key invalid or expired; create a new key in Plane settings",
+                ),
+                Ok(resp) => Check::fail(
+                    "auth",
+                    format!("http {} from {url}", resp.status),
+                    "check PLANE_API_BASE_PATH / the path in PLANE_BASE_URL",
+                ),
+                Err(err) => Check::fail(
+                    "auth",
+                    format!("{err:#}"),
+                    "retry; the host stopped responding",
+                ),
+            },
+        };
+        checks.push(check);
+    }
+
+    for check in &checks {
+        let mark = if check.ok { "pass" } else { "FAIL" };
+        write_stdout_line(&format!("[{mark}] {}: {}", check.name, check.detail))?;
+        if !check.ok {
+            write_stdout_line(&format!("       hint: {}", check.hint))?;
+        }
+    }
+    let failed = checks.iter().filter(|check| !check.ok).count();
+    write_stdout_line(&format!(
+        "{} passed, {failed} failed",
+        checks.len() - failed
+    ))?;
+    if failed > 0 {
+        return Err(anyhow!("doctor found {failed} problem(s)"));
+    }
+    Ok(())
+}
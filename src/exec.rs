@@ -0,0 +1,126 @@
+use crate::command_tree::{CommandTree, Operation};
+use crate::{
+    Target, build_path, coerce_query, dispatch, find_op, join_url, param_default, resolve_api_base,
+    with_default_query, workspace_flag,
+};
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+
+const KEYS: [&str; 5] = ["resource", "op", "path_params", "query", "body"];
+
+/// A `{resource, op, path_params, query, body}` document resolved to its operation.
+#[derive(Debug)]
+pub struct ExecRequest<'a> {
+    pub op: &'a Operation,
+    pub params: HashMap<String, String>,
+    pub query: Vec<(String, String)>,
+    pub body: Option<Value>,
+}
+
+/// Resolves `doc` like the flag path would: abbreviations, `--workspace`/env fallbacks,
+/// and the same errors for unknown ops, deprecated ops, and missing path params.
+pub fn parse<'a>(
+    tree: &'a CommandTree,
+    doc: Value,
+    include_deprecated: bool,
+    workspace: Option<&str>,
+) -> Result<ExecRequest<'a>> {
+    let Value::Object(mut doc) = doc else {
+        return Err(anyhow!("expected a JSON object"));
+    };
+    if let Some(key) = doc.keys().find(|key| !KEYS.contains(&key.as_str())) {
+        return Err(anyhow!("unknown key {key}; expected {}", KEYS.join(", ")));
+    }
+    let name = |doc: &serde_json::Map<String, Value>, key: &str| match doc.get(key) {
+        Some(Value::String(name)) => Ok(name.clone()),
+        Some(other) => Err(anyhow!("{key} must be a string, got {other}")),
+        None => Err(anyhow!("{key} required")),
+    };
+    let (res_name, op_name) = (name(&doc, "resource")?, name(&doc, "op")?);
+    let op = find_op(tree, &res_name, &op_name)?;
+    if op.deprecated && !include_deprecated {
+        return Err(anyhow!(
+            "deprecated endpoint; re-run with --include-deprecated"
+        ));
+    }
+
+    let given = match doc.remove("path_params") {
+        Some(Value::Object(map)) => map,
+        Some(other) => return Err(anyhow!("path_params must be an object, got {other}")),
+        None => Default::default(),
+    };
+    let mut params = HashMap::new();
+    for param in &op.params {
+        let value = match given.get(&param.name).or_else(|| given.get(&param.flag)) {
+            Some(value) => Some(text(value)),
+            None => param_default(param, workspace),
+        };
+        let value = value.ok_or_else(|| anyhow!("missing required argument --{}", param.flag))?;
+        params.insert(param.name.clone(), value);
+    }
+
+    let query = match doc.remove("query") {
+        Some(Value::Object(map)) => map
+            .into_iter()
+            .flat_map(|(key, value)| match value {
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| (key.clone(), text(item)))
+                    .collect::<Vec<_>>(),
+                value => vec![(key, text(&value))],
+            })
+            .collect(),
+        None | Some(Value::Null) => Vec::new(),
+        Some(other) => return Err(anyhow!("query must be an object, got {other}")),
+    };
+
+    Ok(ExecRequest {
+        op,
+        params,
+        query,
+        body: doc.remove("body").filter(|body| !body.is_null()),
+    })
+}
+
+/// Strings as-is; other JSON values in their JSON form (`true`, `3`).
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `plane exec --stdin`: reads one request document and sends it like the matching
+/// generated command would.
+pub fn run(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let mut raw = String::new();
+    std::io::stdin()
+        .read_to_string(&mut raw)
+        .context("read request from stdin")?;
+    let doc: Value = serde_json::from_str(&raw).context("stdin is not valid JSON")?;
+    let request = parse(
+        tree,
+        doc,
+        matches.get_flag("include-deprecated"),
+        workspace_flag(matches),
+    )?;
+
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+    let url = join_url(
+        &api_url,
+        &base_path,
+        &build_path(request.op, &request.params)?,
+    );
+    let query = with_default_query(request.op, request.query);
+    let query = coerce_query(request.op, query, matches.get_flag("strict-query"))?;
+    let target = Target {
+        api_url: &api_url,
+        base_path: &base_path,
+        method: &request.op.method,
+        url: &url,
+        accept: request.op.default_accept.as_deref(),
+    };
+    dispatch(matches, &target, &query, request.body)
+}
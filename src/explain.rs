@@ -0,0 +1,140 @@
+use crate::command_tree::Operation;
+use crate::{
+    Target, bool_query_id, param_fallback, parse_query_pair, workspace_flag, write_stdout_line,
+};
+use anyhow::Result;
+use serde_json::Value;
+
+/// `--explain`: where every path param, query pair, and the body came from.
+pub fn run(
+    op: &Operation,
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    query_file: &[(String, String)],
+    body: Option<&Value>,
+) -> Result<()> {
+    for line in lines(op, matches, target, query, query_file, body)? {
+        write_stdout_line(&line)?;
+    }
+    Ok(())
+}
+
+pub fn lines(
+    op: &Operation,
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    query_file: &[(String, String)],
+    body: Option<&Value>,
+) -> Result<Vec<String>> {
+    let mut out = vec![format!("{} {}", target.method, target.url)];
+    out.push(format!("  template: {}", op.path));
+
+    out.push("path params:".to_string());
+    if op.params.is_empty() {
+        out.push("  (none)".to_string());
+    }
+    for param in &op.params {
+        let (value, source) = match matches.get_one::<String>(&param.name) {
+            Some(value) => (value.clone(), format!("--{}", param.flag)),
+            None => match param_fallback(param, workspace_flag(matches)) {
+                Some((flag, value)) if flag.starts_with("--") => (value, flag.to_string()),
+                Some((var, value)) => (value, format!("env {var}")),
+                None => ("<missing>".to_string(), "unset".to_string()),
+            },
+        };
+        out.push(format!("  {} = {value}  ({source})", param.name));
+    }
+
+    out.push("query:".to_string());
+    if query.is_empty() {
+        out.push("  (none)".to_string());
+    }
+    for (key, value) in query {
+        out.push(format!(
+            "  {key} = {value}  ({})",
+            query_source(op, matches, query_file, key)?
+        ));
+    }
+
+    match body {
+        None => out.push("body: none".to_string()),
+        Some(body) => {
+            out.push(format!("body: ({})", body_source(matches)));
+            for line in serde_json::to_string_pretty(body)?.lines() {
+                out.push(format!("  {line}"));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Mirrors the order `build_query_params` applies: explicit `--query` wins.
+fn query_source(
+    op: &Operation,
+    matches: &clap::ArgMatches,
+    query_file: &[(String, String)],
+    key: &str,
+) -> Result<String> {
+    for raw in matches.get_many::<String>("query").into_iter().flatten() {
+        if parse_query_pair(raw)?.0 == key {
+            return Ok("--query".to_string());
+        }
+    }
+    if let Some(path) = matches.get_one::<String>("query-file")
+        && query_file.iter().any(|(name, _)| name == key)
+    {
+        return Ok(format!("--query-file {path}"));
+    }
+    let flag = match key {
+        "fields" if matches.contains_id("fields") => Some("--fields"),
+        "expand" if matches.contains_id("expand") => Some("--expand"),
+        "per_page" if matches.contains_id("per-page") => Some("--per-page"),
+        "cursor" if matches.contains_id("cursor") => Some("--cursor"),
+        "order_by" if matches.contains_id("order-by") => Some("--order-by"),
+        _ => None,
+    };
+    if let Some(flag) = flag {
+        return Ok(flag.to_string());
+    }
+    if let Some(param) = op.query.iter().find(|query| query.name == key) {
+        let set = matches
+            .try_get_one::<String>(&bool_query_id(param))
+            .ok()
+            .flatten()
+            .is_some();
+        if set {
+            return Ok(format!("--{}", param.flag()));
+        }
+    }
+    if op.default_query.iter().any(|(name, _)| name == key) {
+        return Ok("operation default".to_string());
+    }
+    Ok("flag".to_string())
+}
+
+fn body_source(matches: &clap::ArgMatches) -> String {
+    if let Some(path) = matches.get_one::<String>("body-file") {
+        return format!("--body-file {path}");
+    }
+    if let Some(path) = matches.get_one::<String>("body-base64-file") {
+        return format!("--body-base64-file {path}");
+    }
+    if let Some(path) = matches.get_one::<String>("body-template") {
+        let vars: Vec<&str> = matches
+            .get_many::<String>("var")
+            .into_iter()
+            .flatten()
+            .map(|raw| raw.split_once('=').map_or(raw.as_str(), |(key, _)| key))
+            .collect();
+        if vars.is_empty() {
+            return format!("--body-template {path}");
+        }
+        return format!("--body-template {path} with --var {}", vars.join(", "));
+    }
+    if matches.contains_id("field") || matches.contains_id("field-json") {
+        return "--field".to_string();
+    }
+    "--body-json".to_string()
+}
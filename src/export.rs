@@ -0,0 +1,220 @@
+use crate::command_tree::{CommandTree, Operation};
+use crate::http::{HttpClient, ensure_success};
+use crate::pagination::{self, PageRange, PageRequest};
+use crate::{
+    build_client_with_key, build_path, is_project_param, join_url, pacer, resolve_api_base,
+    resolve_api_key, uses_workspace_default, with_default_query, write_stdout_line,
+};
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// A list operation `plane export` backs up, and the file it is written to.
+#[derive(Debug)]
+pub struct ExportOp<'a> {
+    pub file: String,
+    pub op: &'a Operation,
+    pub per_project: bool,
+}
+
+/// Non-deprecated GET list ops whose only path params are the workspace and
+/// (optionally) a project. Ops needing other ids (an issue, a cycle) are skipped.
+pub fn export_ops(tree: &CommandTree) -> Vec<ExportOp<'_>> {
+    let mut ops = Vec::new();
+    for res in &tree.resources {
+        for op in &res.ops {
+            let is_list =
+                op.name == "list" || op.name.starts_with("list-") || op.name.ends_with("-list");
+            if op.deprecated || op.method != "GET" || !is_list {
+                continue;
+            }
+            let has_workspace = op.params.iter().any(uses_workspace_default);
+            let scoped = op
+                .params
+                .iter()
+                .all(|param| uses_workspace_default(param) || is_project_param(&param.name));
+            if !has_workspace || !scoped {
+                continue;
+            }
+            let file = if op.name == "list" {
+                res.name.clone()
+            } else {
+                format!("{}-{}", res.name, op.name)
+            };
+            ops.push(ExportOp {
+                file,
+                op,
+                per_project: op.params.iter().any(|param| is_project_param(&param.name)),
+            });
+        }
+    }
+    ops
+}
+
+/// Items of a combined page array, a `results` envelope, or a single object.
+fn items(body: Value) -> Vec<Value> {
+    match body {
+        Value::Array(items) => items,
+        Value::Object(mut map) if map.get("results").is_some_and(Value::is_array) => {
+            match map.remove("results") {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            }
+        }
+        other => vec![other],
+    }
+}
+
+/// `plane export`: every exportable list op, all pages, one `<dir>/<file>.json` each.
+/// Project-scoped lists are fetched per project and concatenated.
+pub fn run(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    crate::interrupt::install();
+    let workspace = matches
+        .get_one::<String>("workspace")
+        .cloned()
+        .or_else(|| std::env::var("PLANE_WORKSPACE").ok())
+        .ok_or_else(|| anyhow!("--workspace (or PLANE_WORKSPACE) required"))?;
+    let dir = Path::new(
+        matches
+            .get_one::<String>("dir")
+            .ok_or_else(|| anyhow!("--dir required"))?,
+    );
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+    let ops = export_ops(tree);
+    // One pacer for the project listing and every worker, so --min-interval holds overall.
+    let pacer = pacer(matches);
+    let key = resolve_api_key(matches)?;
+    let client = build_client_with_key(matches, "GET", key.clone())?.with_pacer(pacer.clone());
+
+    let url_for = |op: &Operation, project: Option<&str>| -> Result<String> {
+        let mut params = HashMap::new();
+        for param in &op.params {
+            let value = if uses_workspace_default(param) {
+                workspace.clone()
+            } else {
+                project.unwrap_or_default().to_string()
+            };
+            params.insert(param.name.clone(), value);
+        }
+        Ok(join_url(&api_url, &base_path, &build_path(op, &params)?))
+    };
+    let fetch = |client: &HttpClient, op: &Operation, url: &str| -> Result<Vec<Value>> {
+        let query = with_default_query(op, Vec::new());
+        let request = PageRequest {
+            method: "GET",
+            url,
+            query: &query,
+            body: None,
+            stop: Some(crate::interrupt::flag()),
+        };
+        let response =
+            pagination::fetch_pages(client, request, PageRange::all(), None, &mut |_| {})?;
+        ensure_success(response.status, url, &response.body)?;
+        Ok(items(response.body))
+    };
+
+    let projects_op = ops
+        .iter()
+        .find(|export| export.file == "project")
+        .ok_or_else(|| anyhow!("command tree has no project list operation"))?;
+    let projects =
+        fetch(&client, projects_op.op, &url_for(projects_op.op, None)?).context("list projects")?;
+    let project_ids: Vec<String> = projects
+        .iter()
+        .filter_map(|project| project.get("id").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+
+    let mut jobs = Vec::new();
+    for (idx, export) in ops.iter().enumerate() {
+        if export.file == "project" {
+            continue;
+        }
+        if export.per_project {
+            for id in &project_ids {
+                jobs.push((idx, url_for(export.op, Some(id))?));
+            }
+        } else {
+            jobs.push((idx, url_for(export.op, None)?));
+        }
+    }
+
+    let collected: Mutex<BTreeMap<usize, Vec<Value>>> = Mutex::new(BTreeMap::new());
+    let failed = AtomicUsize::new(0);
+    let next = AtomicUsize::new(0);
+    let workers =
+        (*matches.get_one::<usize>("concurrency").unwrap_or(&4)).clamp(1, jobs.len().max(1));
+    thread::scope(|scope| -> Result<()> {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| -> Result<()> {
+                    let client = build_client_with_key(matches, "GET", key.clone())?
+                        .with_pacer(pacer.clone());
+                    while !crate::interrupt::requested() {
+                        let job = next.fetch_add(1, Ordering::SeqCst);
+                        let Some((idx, url)) = jobs.get(job) else {
+                            return Ok(());
+                        };
+                        match fetch(&client, ops[*idx].op, url) {
+                            Ok(found) => collected
+                                .lock()
+                                .unwrap_or_else(|err| err.into_inner())
+                                .entry(*idx)
+                                .or_default()
+                                .extend(found),
+                            Err(err) => {
+                                failed.fetch_add(1, Ordering::SeqCst);
+                                eprintln!("FAIL {}: {err:#}", ops[*idx].file);
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle
+                .join()
+                .map_err(|_| anyhow!("export worker panicked"))??;
+        }
+        Ok(())
+    })?;
+    // An interrupted export would leave files silently missing items, so write none.
+    crate::interrupt::check()?;
+
+    fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let mut collected = collected
+        .into_inner()
+        .unwrap_or_else(|err| err.into_inner());
+    let mut total = 0;
+    for (idx, export) in ops.iter().enumerate() {
+        let found = if export.file == "project" {
+            projects.clone()
+        } else {
+            collected.remove(&idx).unwrap_or_default()
+        };
+        let path = dir.join(format!("{}.json", export.file));
+        fs::write(&path, serde_json::to_string_pretty(&found)?)
+            .with_context(|| format!("write {}", path.display()))?;
+        write_stdout_line(&format!("{}: {} items", export.file, found.len()))?;
+        total += found.len();
+    }
+    write_stdout_line(&format!(
+        "wrote {} files ({total} items, {} projects) to {}",
+        ops.len(),
+        project_ids.len(),
+        dir.display()
+    ))?;
+    let failed = failed.into_inner();
+    if failed > 0 {
+        return Err(anyhow!(
+            "{failed} list request(s) failed; their files are incomplete"
+        ));
+    }
+    Ok(())
+}
@@ -1,7 +1,19 @@
-use anyhow::{Context, Result, anyhow};
-use reqwest::blocking::{Client, Response};
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use reqwest::blocking::{Body, Client, RequestBuilder, Response};
 use reqwest::Method;
 use serde_json::{Map, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug)]
 pub struct ResponseData {
@@ -10,18 +22,302 @@ pub struct ResponseData {
     pub body: Value,
 }
 
+#[derive(Debug)]
+pub enum Download {
+    Saved(DownloadData),
+    Failed(ResponseData),
+}
+
+#[derive(Debug)]
+pub struct DownloadData {
+    pub status: u16,
+    pub bytes: u64,
+    pub content_type: Option<String>,
+}
+
+/// Bodies smaller than this are sent uncompressed even with `--compress`.
+pub const COMPRESS_THRESHOLD: usize = 8 * 1024;
+
 pub struct HttpClient {
     client: Client,
     api_key: String,
+    headers: Vec<(String, String)>,
+    accept: String,
+    compress: bool,
+    retries: u32,
+    retry_timeout: Option<Duration>,
+    retry_statuses: Vec<u16>,
+    max_response_size: Option<u64>,
+    verbose: bool,
+    skew_checked: Cell<bool>,
+    pacer: Option<Pacer>,
+    counters: Option<Arc<Counters>>,
+    timings: Option<RefCell<Vec<Timing>>>,
+    method_override: bool,
+}
+
+/// Requests sent (retries included), retries, response bytes, and responses by status.
+#[derive(Debug, Default)]
+pub struct Counters {
+    requests: AtomicU64,
+    retries: AtomicU64,
+    bytes: AtomicU64,
+    statuses: Mutex<BTreeMap<u16, u64>>,
+}
+
+impl Counters {
+    fn response(&self, status: u16, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        *self
+            .statuses
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(status)
+            .or_default() += 1;
+    }
+
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn statuses(&self) -> BTreeMap<u16, u64> {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+/// `--min-interval` state. Clones share the last slot handed out, so every client
+/// holding one stays on a single schedule.
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    interval: Duration,
+    last: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Pacer {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Reserves the next start time, `interval` after the previous one, and sleeps
+    /// until it. The lock is released before sleeping.
+    pub fn wait(&self) {
+        let slot = {
+            let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+            let now = Instant::now();
+            let slot = last.map_or(now, |prev| (prev + self.interval).max(now));
+            *last = Some(slot);
+            slot
+        };
+        thread::sleep(slot.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// Wall-clock for one request: `ttfb` until response headers, `total` including the body.
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub ttfb: Duration,
+    pub total: Duration,
+    pub bytes: u64,
+}
+
+/// Settings applied when building the underlying reqwest client.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub user_agent: String,
+    /// Budget for the whole request (connect, send, and reading the body), per attempt;
+    /// reqwest's blocking default of 30s applies when unset.
+    pub timeout: Option<Duration>,
+    /// Budget for establishing the TCP/TLS connection only.
+    pub connect_timeout: Option<Duration>,
+    pub http_version: HttpVersion,
+}
+
+/// `Auto` negotiates via ALPN; the others are escape hatches for proxies that break it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    Http1,
+    Http2PriorKnowledge,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: default_user_agent(),
+            timeout: None,
+            connect_timeout: None,
+            http_version: HttpVersion::Auto,
+        }
+    }
+}
+
+/// `timings: N requests, min/median/max` line; `None` for fewer than two requests.
+pub fn summarize_timings(timings: &[Timing]) -> Option<String> {
+    if timings.len() < 2 {
+        return None;
+    }
+    let mut totals: Vec<Duration> = timings.iter().map(|timing| timing.total).collect();
+    totals.sort();
+    let bytes: u64 = timings.iter().map(|timing| timing.bytes).sum();
+    Some(format!(
+        "timings: {} requests, min {}ms, median {}ms, max {}ms, {bytes} bytes",
+        totals.len(),
+        totals[0].as_millis(),
+        totals[totals.len() / 2].as_millis(),
+        totals[totals.len() - 1].as_millis()
+    ))
+}
+
+/// Parses `30`, `30s`, `1.5s`, `500ms`, or `2m`; bare numbers are seconds.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (number, scale) = if let Some(ms) = input.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(secs) = input.strip_suffix('s') {
+        (secs, 1.0)
+    } else if let Some(mins) = input.strip_suffix('m') {
+        (mins, 60.0)
+    } else {
+        (input, 1.0)
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration {input:?}; expected e.g. 30s, 500ms, 2m"))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(anyhow::anyhow!("invalid duration {input:?}"));
+    }
+    Ok(Duration::from_secs_f64(value * scale))
+}
+
+pub fn default_user_agent() -> String {
+    format!("plane-cli/{}", env!("CARGO_PKG_VERSION"))
 }
 
 impl HttpClient {
-    pub fn new(api_key: String) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent("plane-cli")
-            .build()
-            .context("build http client")?;
-        Ok(Self { client, api_key })
+    pub fn new(api_key: String, options: ClientOptions) -> Result<Self> {
+        let mut builder = Client::builder().user_agent(options.user_agent);
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        builder = match options.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+        let client = builder.build().context("build http client")?;
+        Ok(Self {
+            client,
+            api_key,
+            headers: Vec::new(),
+            accept: "application/json".to_string(),
+            compress: false,
+            retries: 0,
+            retry_timeout: None,
+            retry_statuses: DEFAULT_RETRY_STATUSES.to_vec(),
+            max_response_size: Some(DEFAULT_MAX_RESPONSE_SIZE),
+            verbose: false,
+            skew_checked: Cell::new(false),
+            pacer: None,
+            counters: None,
+            timings: None,
+            method_override: false,
+        })
+    }
+
+    /// Tunnels PATCH/PUT/DELETE as POST with `X-HTTP-Method-Override` for proxies
+    /// that block those methods.
+    pub fn with_method_override(mut self, enabled: bool) -> Self {
+        self.method_override = enabled;
+        self
+    }
+
+    /// Prints per-request timings to stderr and keeps them for `timing_summary`.
+    pub fn with_timings(mut self, enabled: bool) -> Self {
+        self.timings = enabled.then(|| RefCell::new(Vec::new()));
+        self
+    }
+
+    /// Tallies requests, retries, and responses into `counters` (shareable across clients).
+    pub fn with_counters(mut self, counters: Arc<Counters>) -> Self {
+        self.counters = Some(counters);
+        self
+    }
+
+    /// Spaces request starts (retries included) by `pacer`'s interval, whatever the server's
+    /// rate-limit headers say; clone one `Pacer` into several clients to keep one rate.
+    pub fn with_pacer(mut self, pacer: Option<Pacer>) -> Self {
+        self.pacer = pacer;
+        self
+    }
+
+    /// Retries `execute` up to `retries` times on transient statuses and transport errors.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Stops retrying once the next backoff would end past `limit` since the first
+    /// attempt; with `with_retries` too, whichever runs out first wins. It only gates
+    /// starting an attempt: each one still gets the client's full per-attempt timeout.
+    pub fn with_retry_timeout(mut self, limit: Option<Duration>) -> Self {
+        self.retry_timeout = limit;
+        self
+    }
+
+    /// Replaces `DEFAULT_RETRY_STATUSES` as the statuses that trigger a retry.
+    pub fn with_retry_on(mut self, statuses: Vec<u16>) -> Self {
+        self.retry_statuses = statuses;
+        self
+    }
+
+    /// Caps buffered response bodies (`None` for no cap); `download` streams to disk
+    /// and is not limited.
+    pub fn with_max_response_size(mut self, limit: Option<u64>) -> Self {
+        self.max_response_size = limit;
+        self
+    }
+
+    /// Logs retry scheduling details and clock skew to stderr.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Overrides the default `accept: application/json`.
+    pub fn with_accept(mut self, accept: impl Into<String>) -> Self {
+        self.accept = accept.into();
+        self
+    }
+
+    /// Gzip request bodies above `COMPRESS_THRESHOLD`.
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Adds a header sent with every request from this client.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
     }
 
     pub fn execute(
@@ -31,24 +327,468 @@ impl HttpClient {
         query: &[(String, String)],
         body: Option<Value>,
     ) -> Result<ResponseData> {
-        let method = Method::from_bytes(method.as_bytes()).context("invalid http method")?;
+        let mut attempt = 0;
+        let first_attempt = Instant::now();
+        let replay_safe = self.replay_safe(method);
+        let response = loop {
+            let req = self.build_request(method, url, query, body.as_ref())?;
+            self.before_send();
+            let started = Instant::now();
+            let (reason, outcome) = match req.send() {
+                Ok(resp) => {
+                    let response = self.read_timed(method, url, resp, started)?;
+                    if attempt >= self.retries
+                        || !self.retry_statuses.contains(&response.status)
+                        || !(replay_safe || is_unprocessed_status(response.status))
+                    {
+                        break response;
+                    }
+                    (format!("http {}", response.status), Ok(response))
+                }
+                Err(err) if attempt < self.retries && is_retryable_error(&err, replay_safe) => {
+                    let err = anyhow::Error::from(err);
+                    (format!("{err:#}"), Err(err))
+                }
+                Err(err) => return Err(err).context("send request"),
+            };
+            attempt += 1;
+            let delay = backoff(attempt);
+            if let Some(limit) = self.retry_timeout {
+                let elapsed = first_attempt.elapsed();
+                if elapsed + delay > limit {
+                    eprintln!(
+                        "warning: {reason}; retry budget of {limit:?} spent after {} attempt(s)",
+                        attempt
+                    );
+                    match outcome {
+                        Ok(response) => break response,
+                        Err(err) => return Err(err.context("send request")),
+                    }
+                }
+                if self.verbose {
+                    eprintln!(
+                        "verbose: retry {attempt} in {delay:?}, {:?} of retry budget left",
+                        limit - elapsed - delay
+                    );
+                }
+            } else if self.verbose {
+                eprintln!("verbose: retry {attempt} in {delay:?}");
+            }
+            if let Some(counters) = &self.counters {
+                counters.retries.fetch_add(1, Ordering::Relaxed);
+            }
+            match self.retries {
+                u32::MAX => eprintln!("warning: {reason}; retrying ({attempt})"),
+                retries => eprintln!("warning: {reason}; retrying ({attempt}/{retries})"),
+            }
+            thread::sleep(delay);
+        };
+        self.check_clock_skew(&response);
+        if response.status == 415 && self.compresses(body.as_ref()) {
+            eprintln!("warning: server rejected gzip request body (415); resending uncompressed");
+            self.before_send();
+            let started = Instant::now();
+            let resp = self
+                .prepare_request(method, url, query, body.as_ref(), false)?
+                .send()
+                .context("send request")?;
+            return self.read_timed(method, url, resp, started);
+        }
+        Ok(response)
+    }
+
+    /// Whether a request that may have reached the server can be sent again: idempotent
+    /// methods, or any method once an `Idempotency-Key` header is set.
+    fn replay_safe(&self, method: &str) -> bool {
+        matches!(
+            normalize_method(method).as_str(),
+            "GET" | "HEAD" | "PUT" | "DELETE" | "OPTIONS" | "TRACE"
+        ) || self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("idempotency-key"))
+    }
+
+    /// Waits for the pacer's next slot, then counts the request.
+    fn before_send(&self) {
+        if let Some(pacer) = &self.pacer {
+            pacer.wait();
+        }
+        if let Some(counters) = &self.counters {
+            counters.requests.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Under `--verbose`, compares the first response's `Date` header to local time;
+    /// skew breaks signed/expiring auth on some self-hosted setups.
+    fn check_clock_skew(&self, response: &ResponseData) {
+        if !self.verbose || self.skew_checked.replace(true) {
+            return;
+        }
+        let Some(date) = response.headers.get("date").and_then(Value::as_str) else {
+            return;
+        };
+        if let Some(skew) = clock_skew(date, SystemTime::now())
+            && skew.unsigned_abs() > CLOCK_SKEW_THRESHOLD.as_secs()
+        {
+            eprintln!("verbose: server clock differs by {skew}s; check NTP");
+        }
+    }
+
+    fn read_timed(
+        &self,
+        method: &str,
+        url: &str,
+        resp: Response,
+        started: Instant,
+    ) -> Result<ResponseData> {
+        let ttfb = started.elapsed();
+        let (response, bytes) = read_response(resp, self.max_response_size)?;
+        self.record(
+            method,
+            url,
+            response.status,
+            Timing {
+                ttfb,
+                total: started.elapsed(),
+                bytes,
+            },
+        );
+        Ok(response)
+    }
+
+    fn record(&self, method: &str, url: &str, status: u16, timing: Timing) {
+        if let Some(counters) = &self.counters {
+            counters.response(status, timing.bytes);
+        }
+        let Some(timings) = &self.timings else { return };
+        eprintln!(
+            "timing: {method} {url} http {status}: ttfb {}ms, total {}ms, {} bytes",
+            timing.ttfb.as_millis(),
+            timing.total.as_millis(),
+            timing.bytes
+        );
+        timings.borrow_mut().push(timing);
+    }
+
+    /// `min/median/max` of total request time when more than one request was sent.
+    pub fn timing_summary(&self) -> Option<String> {
+        summarize_timings(&self.timings.as_ref()?.borrow())
+    }
+
+    /// Timings recorded so far (empty without `with_timings`), e.g. to merge the
+    /// clients of several workers into one `summarize_timings`.
+    pub fn timings(&self) -> Vec<Timing> {
+        self.timings
+            .as_ref()
+            .map(|timings| timings.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn build_request(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(String, String)],
+        body: Option<&Value>,
+    ) -> Result<RequestBuilder> {
+        self.prepare_request(method, url, query, body, self.compress)
+    }
+
+    /// Headers this client sets on the request (reqwest adds `user-agent` and
+    /// `accept-encoding` when sending), for `--trace`.
+    pub fn request_headers(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(String, String)],
+        body: Option<&Value>,
+    ) -> Result<Vec<(String, String)>> {
+        let request = self
+            .build_request(method, url, query, body)?
+            .build()
+            .context("build request")?;
+        Ok(request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.to_string(), value)
+            })
+            .collect())
+    }
+
+    fn compresses(&self, body: Option<&Value>) -> bool {
+        self.compress
+            && body
+                .and_then(|value| serde_json::to_vec(value).ok())
+                .is_some_and(|bytes| bytes.len() > COMPRESS_THRESHOLD)
+    }
+
+    fn prepare_request(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(String, String)],
+        body: Option<&Value>,
+        compress: bool,
+    ) -> Result<RequestBuilder> {
+        let method = parse_method(method)?;
+        let overridden =
+            self.method_override && matches!(method, Method::PATCH | Method::PUT | Method::DELETE);
+        let wire_method = if overridden {
+            Method::POST
+        } else {
+            method.clone()
+        };
         let mut req = self
             .client
-            .request(method, url)
+            .request(wire_method, url)
             .header("x-api-key", &self.api_key)
-            .header("accept", "application/json")
+            .header("accept", &self.accept)
             .query(query);
+        if overridden {
+            req = req.header("x-http-method-override", method.as_str());
+        }
+        for (name, value) in &self.headers {
+            req = req.header(name, value);
+        }
 
         if let Some(value) = body {
-            req = req.header("content-type", "application/json").json(&value);
+            let bytes = serde_json::to_vec(value).context("encode JSON body")?;
+            req = req.header("content-type", "application/json");
+            if compress && bytes.len() > COMPRESS_THRESHOLD {
+                req = req.header("content-encoding", "gzip").body(gzip(&bytes)?);
+            } else {
+                req = req.body(bytes);
+            }
         }
 
+        Ok(req)
+    }
+
+    /// Sends `body` as-is with `content_type`, without buffering it. A stream cannot
+    /// be replayed, so there are no retries and no gzip.
+    pub fn send_stream(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(String, String)],
+        body: Body,
+        content_type: &str,
+    ) -> Result<ResponseData> {
+        let req = self
+            .prepare_request(method, url, query, None, false)?
+            .header("content-type", content_type)
+            .body(body);
+        self.before_send();
+        let started = Instant::now();
         let resp = req.send().context("send request")?;
-        parse_response(resp)
+        self.read_timed(method, url, resp, started)
+    }
+
+    /// Streams a successful response body to `dest` without buffering it in memory.
+    /// Error responses are parsed like `execute` so `ensure_success` can report them.
+    pub fn download(
+        &self,
+        method: &str,
+        url: &str,
+        query: &[(String, String)],
+        body: Option<Value>,
+        dest: &Path,
+    ) -> Result<Download> {
+        let req = self.build_request(method, url, query, body.as_ref())?;
+        self.before_send();
+        let started = Instant::now();
+        let mut resp = req.send().context("send request")?;
+        if !resp.status().is_success() {
+            return Ok(Download::Failed(
+                self.read_timed(method, url, resp, started)?,
+            ));
+        }
+        let ttfb = started.elapsed();
+
+        let status = resp.status().as_u16();
+        let content_type = resp
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        if let Some(parent) = dest
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let file = File::create(dest).with_context(|| format!("create {}", dest.display()))?;
+        let mut writer = BufWriter::new(file);
+        let bytes = resp.copy_to(&mut writer).context("read response body")?;
+        writer
+            .flush()
+            .with_context(|| format!("write {}", dest.display()))?;
+        self.record(
+            method,
+            url,
+            status,
+            Timing {
+                ttfb,
+                total: started.elapsed(),
+                bytes,
+            },
+        );
+
+        Ok(Download::Saved(DownloadData {
+            status,
+            bytes,
+            content_type,
+        }))
+    }
+}
+
+/// `Date` has one-second resolution, so smaller differences are noise.
+pub const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Server time minus local time in seconds, from an HTTP `Date` header.
+pub fn clock_skew(date: &str, now: SystemTime) -> Option<i64> {
+    let server = httpdate::parse_http_date(date).ok()?;
+    Some(match server.duration_since(now) {
+        Ok(ahead) => ahead.as_secs() as i64,
+        Err(behind) => -(behind.duration().as_secs() as i64),
+    })
+}
+
+/// Rate limiting and gateway/server hiccups; other statuses are returned as-is.
+pub const DEFAULT_RETRY_STATUSES: [u16; 5] = [429, 500, 502, 503, 504];
+
+/// Standard methods in any case (`get`, `Get`); extension methods are kept as given.
+pub fn normalize_method(method: &str) -> String {
+    const STANDARD: [&str; 9] = [
+        "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+    ];
+    match STANDARD
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(method))
+    {
+        Some(name) => name.to_string(),
+        None => method.to_string(),
+    }
+}
+
+pub fn parse_method(method: &str) -> Result<Method> {
+    Method::from_bytes(normalize_method(method).as_bytes()).context("invalid http method")
+}
+
+/// Parses `--retry-on`: comma-separated codes and inclusive ranges, e.g. `429,500-504,522`.
+pub fn parse_status_list(input: &str) -> Result<Vec<u16>> {
+    let status = |raw: &str| -> Result<u16> {
+        raw.trim()
+            .parse()
+            .ok()
+            .filter(|code| (100..=599).contains(code))
+            .ok_or_else(|| anyhow::anyhow!("invalid status {raw:?} in {input:?}; expected 100-599"))
+    };
+    let mut statuses = Vec::new();
+    for part in input.split(',') {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (status(start)?, status(end)?);
+                if end < start {
+                    return Err(anyhow::anyhow!("invalid status range {part:?}"));
+                }
+                statuses.extend(start..=end);
+            }
+            None => statuses.push(status(part)?),
+        }
     }
+    Ok(statuses)
 }
 
-fn parse_response(resp: Response) -> Result<ResponseData> {
+/// Refused/failed connections never reached the server and are always retried.
+/// Timeouts and connections dropped mid-request may have, so they are retried only
+/// when `replay_safe`. Builder errors (invalid URL, bad header) and redirect loops
+/// are never retried.
+pub fn is_retryable_error(err: &reqwest::Error, replay_safe: bool) -> bool {
+    err.is_connect() || (replay_safe && (err.is_timeout() || err.is_request()))
+}
+
+/// 429 and 503 mean the server turned the request away, so even a POST can be resent;
+/// any other error status may follow a partially applied request.
+fn is_unprocessed_status(status: u16) -> bool {
+    matches!(status, 429 | 503)
+}
+
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(250 << attempt.min(5))
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).context("gzip request body")?;
+    encoder.finish().context("gzip request body")
+}
+
+/// Buffered bodies larger than this fail instead of exhausting memory.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Rejects an oversized `Content-Length` up front, then stops reading one byte past
+/// `limit` for bodies without one (chunked) or that lie about it.
+fn read_limited(mut resp: Response, limit: Option<u64>) -> Result<String> {
+    let too_large = |limit: u64| {
+        anyhow::anyhow!(
+            "response body exceeds --max-response-size of {limit} bytes; use --download to stream it to a file"
+        )
+    };
+    let mut raw = Vec::new();
+    match limit {
+        Some(limit) => {
+            if resp.content_length().is_some_and(|length| length > limit) {
+                return Err(too_large(limit));
+            }
+            (&mut resp)
+                .take(limit + 1)
+                .read_to_end(&mut raw)
+                .context("read response body")?;
+            if raw.len() as u64 > limit {
+                return Err(too_large(limit));
+            }
+        }
+        None => {
+            resp.read_to_end(&mut raw).context("read response body")?;
+        }
+    }
+    Ok(String::from_utf8(raw)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()))
+}
+
+/// `--max-response-size`: bytes, or with a `KB`/`MB`/`GB` suffix (powers of 1024).
+pub fn parse_size(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let upper = input.to_ascii_uppercase();
+    let (number, scale) = [
+        ("GB", 1u64 << 30),
+        ("MB", 1 << 20),
+        ("KB", 1 << 10),
+        ("B", 1),
+    ]
+    .into_iter()
+    .find_map(|(suffix, scale)| {
+        upper
+            .strip_suffix(suffix)
+            .map(|number| (number.to_string(), scale))
+    })
+    .unwrap_or((upper.clone(), 1));
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(scale))
+        .ok_or_else(|| {
+            anyhow::anyhow!("invalid size {input:?}; expected e.g. 1048576, 512KB, 64MB, 1GB")
+        })
+}
+
+/// Parsed response plus the body size in bytes.
+fn read_response(resp: Response, limit: Option<u64>) -> Result<(ResponseData, u64)> {
     let status = resp.status().as_u16();
     let mut headers = Map::new();
     for (key, value) in resp.headers().iter() {
@@ -57,22 +797,118 @@ fn parse_response(resp: Response) -> Result<ResponseData> {
         }
     }
 
-    let text = resp.text().context("read response body")?;
+    let text = read_limited(resp, limit)?;
+    let bytes = text.len() as u64;
     let body = match serde_json::from_str::<Value>(&text) {
         Ok(value) => value,
         Err(_) => Value::String(text),
     };
 
-    Ok(ResponseData {
-        status,
-        headers,
-        body,
-    })
+    Ok((
+        ResponseData {
+            status,
+            headers,
+            body,
+        },
+        bytes,
+    ))
 }
 
-pub fn ensure_success(status: u16, body: &Value) -> Result<()> {
+#[derive(Debug)]
+pub struct HttpError {
+    pub status: u16,
+    pub url: String,
+    pub body: Value,
+}
+
+impl HttpError {
+    /// Best-effort human message: Plane error fields first, then the raw body. HTML
+    /// error pages (misconfigured gateways) are summarized instead of dumped.
+    pub fn message(&self) -> String {
+        self.render(false)
+    }
+
+    /// `message()`, with a non-string JSON body pretty-printed when `pretty`.
+    fn render(&self, pretty: bool) -> String {
+        if looks_like_html(&self.body) {
+            return format!(
+                "received HTML error page, status {}; use --raw to see it",
+                self.status
+            );
+        }
+        if let Value::Object(map) = &self.body {
+            for key in ["error", "detail", "message"] {
+                if let Some(Value::String(msg)) = map.get(key) {
+                    return msg.clone();
+                }
+            }
+        }
+        match &self.body {
+            Value::String(text) => text.clone(),
+            other if pretty => {
+                serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string())
+            }
+            other => other.to_string(),
+        }
+    }
+
+    /// `message()` cut to `limit` characters, pointing at `--raw` when shortened.
+    pub fn summary(&self, limit: usize) -> String {
+        truncate_message(self.message(), limit)
+    }
+
+    /// `summary` for `--pretty`: JSON bodies are indented before truncation.
+    pub fn pretty_summary(&self, limit: usize) -> String {
+        truncate_message(self.render(true), limit)
+    }
+}
+
+fn truncate_message(message: String, limit: usize) -> String {
+    if limit == 0 || message.chars().count() <= limit {
+        return message;
+    }
+    let cut: String = message.chars().take(limit).collect();
+    format!("{cut}... (truncated; use --raw for the full body)")
+}
+
+/// Text bodies starting with `<` (gateway/proxy error pages).
+pub fn looks_like_html(body: &Value) -> bool {
+    matches!(body, Value::String(text) if text.trim_start().starts_with('<'))
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `{:#}` is used for `--pretty`.
+        let summary = if f.alternate() {
+            self.pretty_summary(error_body_limit())
+        } else {
+            self.summary(error_body_limit())
+        };
+        write!(f, "http {}: {summary}", self.status)
+    }
+}
+
+/// Default cap on error text in messages; `PLANE_ERROR_BODY_LIMIT` overrides it
+/// (0 disables truncation).
+pub const ERROR_BODY_LIMIT: usize = 500;
+
+fn error_body_limit() -> usize {
+    std::env::var("PLANE_ERROR_BODY_LIMIT")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(ERROR_BODY_LIMIT)
+}
+
+impl std::error::Error for HttpError {}
+
+pub fn ensure_success(status: u16, url: &str, body: &Value) -> Result<()> {
     if (200..300).contains(&status) {
         return Ok(());
     }
-    Err(anyhow!("http {}: {}", status, body))
+    Err(HttpError {
+        status,
+        url: url.to_string(),
+        body: body.clone(),
+    }
+    .into())
 }
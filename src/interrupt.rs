@@ -0,0 +1,55 @@
+use std::fmt;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit status after Ctrl-C stopped a multi-request run (128 + SIGINT).
+pub const EXIT_CODE: i32 = 130;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static INSTALL: Once = Once::new();
+
+/// Turns the first Ctrl-C into a stop request that `--all`/`--batch` loops check
+/// between requests; a second Ctrl-C exits immediately.
+pub fn install() {
+    INSTALL.call_once(|| {
+        let installed = ctrlc::set_handler(|| {
+            if REQUESTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(EXIT_CODE);
+            }
+            eprintln!("interrupted; finishing the current request (Ctrl-C again to abort)");
+        });
+        if let Err(err) = installed {
+            eprintln!("warning: cannot handle Ctrl-C: {err}");
+        }
+    });
+}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// The stop flag itself, for `PageRequest::stop`.
+pub fn flag() -> &'static AtomicBool {
+    &REQUESTED
+}
+
+/// Returned once an interrupted run has flushed its partial output; `main` maps it
+/// to `EXIT_CODE`.
+#[derive(Debug)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interrupted; output is partial")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// `Err(Interrupted)` if Ctrl-C was pressed.
+pub fn check() -> anyhow::Result<()> {
+    if requested() {
+        return Err(Interrupted.into());
+    }
+    Ok(())
+}
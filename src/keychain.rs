@@ -0,0 +1,70 @@
+use anyhow::Result;
+
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "plane-cli";
+
+#[cfg(feature = "keyring")]
+pub fn store(profile: &str, api_key: &str) -> Result<()> {
+    use anyhow::Context;
+    let entry = keyring::Entry::new(SERVICE, profile).context("open keyring entry")?;
+    entry
+        .set_password(api_key)
+        .context("store api key in keyring")
+}
+
+#[cfg(feature = "keyring")]
+pub fn load(profile: &str) -> Result<Option<String>> {
+    use anyhow::Context;
+    let entry = keyring::Entry::new(SERVICE, profile).context("open keyring entry")?;
+    match entry.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err).context("read api key from keyring"),
+    }
+}
+
+/// No usable keyring on this machine (e.g. no Secret Service on a headless box), as
+/// opposed to a keyring that answered with an error.
+#[cfg(feature = "keyring")]
+pub fn is_unavailable(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<keyring::Error>(),
+        Some(keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_))
+    )
+}
+
+#[cfg(feature = "keyring")]
+pub fn delete(profile: &str) -> Result<bool> {
+    use anyhow::Context;
+    let entry = keyring::Entry::new(SERVICE, profile).context("open keyring entry")?;
+    match entry.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(err) => Err(err).context("delete api key from keyring"),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store(_profile: &str, _api_key: &str) -> Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn load(_profile: &str) -> Result<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn is_unavailable(_err: &anyhow::Error) -> bool {
+    false
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn delete(_profile: &str) -> Result<bool> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn unsupported() -> anyhow::Error {
+    anyhow::anyhow!("built without keyring support; rebuild with --features keyring")
+}
@@ -0,0 +1,279 @@
+//! The Plane CLI's core without the command line: the embedded command tree, the
+//! HTTP client, pagination, and path/URL building. `PlaneClient` ties them together
+//! for embedding; `main.rs` is a thin clap layer over the same pieces.
+
+pub mod command_tree;
+pub mod http;
+pub mod pagination;
+
+use anyhow::{Result, anyhow};
+use command_tree::{CommandTree, Operation, Param};
+use http::{ClientOptions, HttpClient, ResponseData};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Runs command-tree operations by name against one Plane instance.
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// let client = plane::PlaneClient::new("https://api.plane.so", "plane_api_key")?;
+/// let params = HashMap::from([("slug".to_string(), "acme".to_string())]);
+/// let projects = client.execute("project", "list", &params, &[], None)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct PlaneClient {
+    tree: CommandTree,
+    http: HttpClient,
+    api_url: String,
+    base_path: String,
+    workspace: Option<String>,
+}
+
+impl PlaneClient {
+    /// Uses the embedded command tree and default client options.
+    pub fn new(api_url: &str, api_key: &str) -> Result<Self> {
+        let http = HttpClient::new(api_key.to_string(), ClientOptions::default())?;
+        Ok(Self::with_http(
+            command_tree::load_command_tree(),
+            http,
+            api_url,
+        ))
+    }
+
+    /// For a custom tree or a client built with retries, timeouts, or headers.
+    pub fn with_http(tree: CommandTree, http: HttpClient, api_url: &str) -> Self {
+        Self {
+            base_path: tree.base_path.clone(),
+            tree,
+            http,
+            api_url: api_url.to_string(),
+            workspace: None,
+        }
+    }
+
+    /// Overrides the tree's `base_path` (e.g. `/api/v1`).
+    pub fn with_base_path(mut self, base_path: &str) -> Self {
+        self.base_path = base_path.to_string();
+        self
+    }
+
+    /// Slug for workspace params left out of `params`, like the CLI's `--workspace`;
+    /// ahead of `PLANE_WORKSPACE`.
+    pub fn with_workspace(mut self, slug: &str) -> Self {
+        self.workspace = Some(slug.to_string());
+        self
+    }
+
+    pub fn tree(&self) -> &CommandTree {
+        &self.tree
+    }
+
+    /// Accepts the same unique-prefix abbreviations as the CLI.
+    pub fn operation(&self, resource: &str, op: &str) -> Result<&Operation> {
+        find_op(&self.tree, resource, op)
+    }
+
+    /// Full URL for an operation with `params` substituted into its path. Params left
+    /// out fall back like the CLI's (see `param_default`).
+    pub fn url(
+        &self,
+        resource: &str,
+        op: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<String> {
+        let op = self.operation(resource, op)?;
+        let params = resolve_params(op, params, self.workspace.as_deref())?;
+        Ok(join_url(
+            &self.api_url,
+            &self.base_path,
+            &build_path(op, &params)?,
+        ))
+    }
+
+    /// Sends one request (retries per the client) and returns the response, error
+    /// statuses included; see `http::ensure_success` to turn those into errors.
+    pub fn execute(
+        &self,
+        resource: &str,
+        op: &str,
+        params: &HashMap<String, String>,
+        query: &[(String, String)],
+        body: Option<Value>,
+    ) -> Result<ResponseData> {
+        let url = self.url(resource, op, params)?;
+        let operation = self.operation(resource, op)?;
+        let query = with_default_query(operation, query.to_vec());
+        self.http.execute(&operation.method, &url, &query, body)
+    }
+}
+
+/// `params` plus `param_default` for any the caller left out; errors like the CLI
+/// does for a path param that is still missing.
+pub fn resolve_params(
+    op: &Operation,
+    params: &HashMap<String, String>,
+    workspace: Option<&str>,
+) -> Result<HashMap<String, String>> {
+    let mut resolved = params.clone();
+    for param in &op.params {
+        if resolved.contains_key(&param.name) {
+            continue;
+        }
+        let value = param_default(param, workspace)
+            .ok_or_else(|| anyhow!("missing required argument --{}", param.flag))?;
+        resolved.insert(param.name.clone(), value);
+    }
+    Ok(resolved)
+}
+
+/// Fallback when a path param is not given: `workspace` (the CLI's `--workspace`),
+/// then `PLANE_WORKSPACE`, for workspace params; `PLANE_PROJECT_ID` (then
+/// `PLANE_PROJECT`) for project ids.
+pub fn param_default(param: &Param, workspace: Option<&str>) -> Option<String> {
+    param_fallback(param, workspace).map(|(_, value)| value)
+}
+
+/// Like `param_default`, but also names the flag or env var the value came from.
+pub fn param_fallback(param: &Param, workspace: Option<&str>) -> Option<(&'static str, String)> {
+    let from = |var: &'static str| std::env::var(var).ok().map(|value| (var, value));
+    if uses_workspace_default(param) {
+        return workspace
+            .map(|slug| ("--workspace", slug.to_string()))
+            .or_else(|| from("PLANE_WORKSPACE"));
+    }
+    if is_project_param(&param.name) {
+        return from("PLANE_PROJECT_ID").or_else(|| from("PLANE_PROJECT"));
+    }
+    None
+}
+
+/// `project_id`, `projectId`, `parent_project_id`: contains `project` and ends in `id`.
+/// `project_identifier` (the short key, not a UUID) is deliberately excluded.
+pub fn is_project_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.contains("project") && name.ends_with("id")
+}
+
+/// Params that fall back to `PLANE_WORKSPACE`: the built-in names, any param exposed
+/// as `--workspace-slug`, and names listed in `PLANE_WORKSPACE_PARAMS` (comma list).
+pub fn uses_workspace_default(param: &Param) -> bool {
+    let extra = std::env::var("PLANE_WORKSPACE_PARAMS").unwrap_or_default();
+    matches_workspace_param(param, &extra)
+}
+
+/// `uses_workspace_default` with the `PLANE_WORKSPACE_PARAMS` list passed in.
+pub fn matches_workspace_param(param: &Param, extra: &str) -> bool {
+    is_workspace_param(&param.name)
+        || param.flag == "workspace-slug"
+        || extra
+            .split(',')
+            .map(str::trim)
+            .any(|name| name == param.name)
+}
+
+pub fn is_workspace_param(name: &str) -> bool {
+    matches!(
+        name,
+        "slug" | "workspace" | "workspace_slug" | "workspaceSlug"
+    )
+}
+
+/// Resolves resource and op names; unique prefixes match, exact names always win.
+pub fn find_op<'a>(tree: &'a CommandTree, res: &str, op: &str) -> Result<&'a Operation> {
+    let resource = match_prefix(&tree.resources, |r| &r.name, res, "resource")?
+        .ok_or_else(|| anyhow!("unknown command {res} {op}"))?;
+    match_prefix(&resource.ops, |o| &o.name, op, "operation")?
+        .ok_or_else(|| anyhow!("unknown command {res} {op}"))
+}
+
+/// Full resource name for a (possibly abbreviated) input that `find_op` accepted.
+pub fn resource_name<'a>(tree: &'a CommandTree, input: &'a str) -> &'a str {
+    match_prefix(&tree.resources, |r| &r.name, input, "resource")
+        .ok()
+        .flatten()
+        .map_or(input, |res| res.name.as_str())
+}
+
+fn match_prefix<'a, T>(
+    items: &'a [T],
+    name: impl Fn(&T) -> &str,
+    input: &str,
+    kind: &str,
+) -> Result<Option<&'a T>> {
+    if let Some(item) = items.iter().find(|item| name(item) == input) {
+        return Ok(Some(item));
+    }
+    let candidates: Vec<&T> = items
+        .iter()
+        .filter(|item| name(item).starts_with(input))
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [item] => Ok(Some(*item)),
+        many => {
+            let names: Vec<&str> = many.iter().map(|item| name(item)).collect();
+            Err(anyhow!(
+                "ambiguous {kind} '{input}'; candidates: {}",
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+pub fn build_path(op: &Operation, params: &HashMap<String, String>) -> Result<String> {
+    let template = op.path.as_str();
+    let tokens =
+        command_tree::path_tokens(template).ok_or_else(|| anyhow!("invalid path template"))?;
+    let mut out = String::new();
+    let mut cursor = 0;
+    for token in tokens {
+        out.push_str(&template[cursor..token.start]);
+        let value = params
+            .get(token.name)
+            .ok_or_else(|| anyhow!("missing value for {}", token.name))?;
+        out.push_str(value);
+        cursor = token.end;
+    }
+
+    out.push_str(&template[cursor..]);
+    match op.trailing_slash {
+        Some(true) if !out.ends_with('/') => out.push('/'),
+        Some(false) => out.truncate(out.trim_end_matches('/').len()),
+        _ => {}
+    }
+    Ok(out)
+}
+
+pub fn join_url(base: &str, base_path: &str, path: &str) -> String {
+    let base = base.trim_end_matches('/');
+    let base_path = base_path.trim_matches('/');
+    let path = path.trim_start_matches('/');
+
+    if base_path.is_empty() || has_path_prefix(path, base_path) {
+        format!("{base}/{path}")
+    } else if path.is_empty() {
+        format!("{base}/{base_path}")
+    } else {
+        format!("{base}/{base_path}/{path}")
+    }
+}
+
+/// True when `path` already starts with `prefix` on a segment boundary.
+fn has_path_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Seeds the op's `default_query` ahead of the explicit params, skipping keys the
+/// user already set (via `--query`, `--per-page`, a query flag, ...).
+pub fn with_default_query(op: &Operation, query: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut seeded: Vec<(String, String)> = op
+        .default_query
+        .iter()
+        .filter(|(key, _)| !query.iter().any(|(given, _)| given == key))
+        .cloned()
+        .collect();
+    seeded.extend(query);
+    seeded
+}
@@ -1,47 +1,171 @@
-mod command_tree;
-mod http;
+mod alias;
+mod apply;
+mod batch;
+mod clipboard;
+mod config;
+mod diff;
+mod doctor;
+mod exec;
+mod explain;
+mod export;
+mod interrupt;
+mod keychain;
+mod manifest;
+mod metrics;
+mod output;
 #[cfg(test)]
 mod tests;
 
 use anyhow::{Context, Result, anyhow};
 use clap::{Arg, ArgAction, Command};
-use command_tree::{CommandTree, Operation, Param};
-use http::{HttpClient, ensure_success};
-use serde_json::{Value, json};
-use std::{collections::HashMap, env, fs, io::Write};
+use command_tree::{CommandTree, Operation, Param, QueryParam};
+use http::{
+    ClientOptions, Download, HttpClient, HttpError, HttpVersion, ResponseData, ensure_success,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use pagination::{Checkpoint, PageProgress, PageRange, PageRequest, SavedPages};
+use plane::{
+    build_path, command_tree, find_op, http, is_project_param, join_url, pagination, param_default,
+    param_fallback, resource_name, uses_workspace_default, with_default_query,
+};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    io::{IsTerminal, Write},
+};
 
 fn main() {
-    if let Err(err) = run() {
-        eprintln!("error: {err}");
+    let argv = env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let argv = match alias::expand_argv(argv) {
+        Ok(argv) => argv,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut tree = command_tree::load_command_tree();
+    if let Some(raw) = api_version_arg(&argv) {
+        match command_tree::parse_version(&raw) {
+            Some(version) => tree.retain_api_version(&version),
+            None => {
+                eprintln!("error: invalid --api-version {raw}; expected e.g. 1.2 or 0.24.1");
+                std::process::exit(1);
+            }
+        }
+    }
+    let show_deprecated = wants_deprecated(&argv);
+    let matches = build_cli(&tree, show_deprecated).get_matches_from(argv);
+    let json_errors = matches
+        .get_one::<String>("error-format")
+        .is_some_and(|format| format == "json");
+
+    let started = std::time::Instant::now();
+    let result = run(&tree, &matches);
+    if let Some(path) = matches.get_one::<String>("metrics-file") {
+        metrics::write(path, &metrics::snapshot(started.elapsed(), result.is_ok()));
+    }
+    if let Err(err) = result {
+        report_error(&err, json_errors, matches.get_flag("pretty"));
+        if err.is::<interrupt::Interrupted>() {
+            std::process::exit(interrupt::EXIT_CODE);
+        }
         std::process::exit(1);
     }
 }
 
-fn run() -> Result<()> {
-    let tree = command_tree::load_command_tree();
-    let cli = build_cli(&tree);
-    let matches = cli.get_matches();
+fn report_error(err: &anyhow::Error, json_errors: bool, pretty: bool) {
+    if !json_errors {
+        match err.downcast_ref::<HttpError>() {
+            Some(http_err) if pretty && err.chain().count() == 1 => {
+                eprintln!("error: {http_err:#}")
+            }
+            _ => eprintln!("error: {err}"),
+        }
+        return;
+    }
+    eprintln!("{}", error_json(err));
+}
+
+fn error_json(err: &anyhow::Error) -> Value {
+    if let Some(http_err) = err.downcast_ref::<HttpError>() {
+        return json!({
+            "error": http_err.message(),
+            "status": http_err.status,
+            "url": http_err.url,
+            "body": http_err.body,
+        });
+    }
+    let url = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .and_then(|req_err| req_err.url())
+        .map(|url| url.to_string());
+    json!({
+        "error": err.to_string(),
+        "status": null,
+        "url": url,
+    })
+}
+
+fn run(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    load_dotenv(matches)?;
 
     if let Some(matches) = matches.subcommand_matches("list") {
-        return handle_list(&tree, matches);
+        return handle_list(tree, matches);
     }
     if let Some(matches) = matches.subcommand_matches("describe") {
-        return handle_describe(&tree, matches);
+        return handle_describe(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("docs") {
+        return handle_docs(tree, matches);
     }
     if let Some(matches) = matches.subcommand_matches("tree") {
-        return handle_tree(&tree, matches);
+        return handle_tree(tree, matches);
+    }
+    if matches.subcommand_matches("manifest").is_some() {
+        return write_output(&serde_json::to_value(manifest::build(tree))?, true);
+    }
+    if let Some(matches) = matches.subcommand_matches("schema") {
+        return handle_schema(tree, matches);
     }
     if let Some(matches) = matches.subcommand_matches("request") {
-        return handle_request(&tree, &matches);
+        return handle_request(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("exec") {
+        return exec::run(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("apply") {
+        return apply::run(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("export") {
+        return export::run(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("doctor") {
+        return doctor::run(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("whoami") {
+        return handle_whoami(tree, matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("alias") {
+        return handle_alias(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("config") {
+        return handle_config(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("login") {
+        return handle_login(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("logout") {
+        return handle_logout(matches);
     }
 
     let include_deprecated = matches.get_flag("include-deprecated");
 
-    let api_key = env::var("PLANE_API_KEY").context("PLANE_API_KEY missing")?;
-    let (api_url, base_path) = resolve_api_base(&tree)?;
-
-    let pretty = matches.get_flag("pretty");
-    let raw = matches.get_flag("raw");
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
 
     let (res_name, res_matches) = matches
         .subcommand()
@@ -50,43 +174,157 @@ fn run() -> Result<()> {
         .subcommand()
         .ok_or_else(|| anyhow!("operation required"))?;
 
-    let op = find_op(&tree, res_name, op_name)
-        .ok_or_else(|| anyhow!("unknown command {res_name} {op_name}"))?;
+    let op = find_op(tree, res_name, op_name)?;
+    verbose(matches, matched_op_note(resource_name(tree, res_name), op));
 
     if op.deprecated && !include_deprecated {
         return Err(anyhow!("deprecated endpoint; re-run with --include-deprecated"));
     }
 
+    if let Some(source) = matches.get_one::<String>("batch") {
+        return batch::run(matches, op_matches, op, &api_url, &base_path, source);
+    }
+
     let params = collect_path_params(op, op_matches)?;
-    let path = build_path(&op.path, &params)?;
+    let path = build_path(op, &params)?;
     let url = join_url(&api_url, &base_path, &path);
 
-    let query = build_query_params(op_matches)?;
+    let query_file = read_query_file(op_matches)?;
+    let mut query = build_query_params(op_matches, &query_file)?;
+    query.extend(bool_query_params(op, op_matches));
+    let query = with_default_query(op, query);
+    let query = coerce_query(op, query, matches.get_flag("strict-query"))?;
+    check_query_keys(op, op_matches, &query_file)?;
+    check_expand_keys(op, op_matches)?;
     let body = read_body(op_matches)?;
 
-    let client = HttpClient::new(api_key)?;
-    let response = client.execute(&op.method, &url, &query, body)?;
-
-    let output = if raw {
-        json!({
-            "status": response.status,
-            "headers": response.headers,
-            "body": response.body,
-        })
-    } else {
-        response.body
+    let target = Target {
+        api_url: &api_url,
+        base_path: &base_path,
+        method: &op.method,
+        url: &url,
+        accept: op.default_accept.as_deref(),
     };
+    if let Ok(Some(dest)) = op_matches.try_get_one::<String>("download")
+        && !matches.get_flag("dry-run")
+        && !matches.get_flag("print-url")
+    {
+        return handle_download(matches, &target, &query, body, dest);
+    }
+    if matches.get_flag("explain") {
+        return explain::run(op, op_matches, &target, &query, &query_file, body.as_ref());
+    }
+    if matches.get_flag("diff") {
+        return diff::run(matches, &target, body);
+    }
+    dispatch(matches, &target, &query, body)
+}
 
-    write_output(&output, pretty)?;
-    ensure_success(response.status, &output)?;
-    Ok(())
+/// clap is built before parsing, so argv is scanned for `--include-deprecated` up
+/// front to decide whether deprecated ops show up in `--help`.
+fn wants_deprecated(argv: &[String]) -> bool {
+    argv.iter()
+        .skip(1)
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--include-deprecated")
+}
+
+/// `--api-version` has to be known before the CLI is built, since it decides which
+/// operations exist at all.
+fn api_version_arg(argv: &[String]) -> Option<String> {
+    let mut args = argv.iter().skip(1).take_while(|arg| *arg != "--");
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--api-version=") {
+            return Some(value.to_string());
+        }
+        if arg == "--api-version" {
+            return args.next().cloned();
+        }
+    }
+    None
 }
 
-fn build_cli(tree: &CommandTree) -> Command {
+/// Deprecated ops are hidden from help unless `show_deprecated` is set; they parse
+/// either way.
+fn build_cli(tree: &CommandTree, show_deprecated: bool) -> Command {
     let mut cmd = Command::new("plane")
         .about("Plane CLI (auto-generated)")
+        .infer_subcommands(true)
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(
+            Arg::new("api-key")
+                .long("api-key")
+                .global(true)
+                .value_name("KEY")
+                .help("API key (overrides PLANE_API_KEY)"),
+        )
+        .arg(
+            Arg::new("api-version")
+                .long("api-version")
+                .global(true)
+                .value_name("X")
+                .help("Hide operations the command tree marks unavailable on this Plane API version"),
+        )
+        .arg(
+            Arg::new("api-key-file")
+                .long("api-key-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Read API key from file (overrides PLANE_API_KEY)"),
+        )
+        .arg(
+            Arg::new("api-key-command")
+                .long("api-key-command")
+                .global(true)
+                .value_name("CMD")
+                .help("Run command (via sh -c, or cmd /C on Windows) and use its stdout as API key"),
+        )
+        .arg(
+            Arg::new("env-file")
+                .long("env-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Load env vars from file (existing env wins)"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .global(true)
+                .value_name("NAME")
+                .help(
+                    "Profile for the keyring API key and config.toml settings [default: default]",
+                ),
+        )
+        .arg(
+            Arg::new("api-url")
+                .long("api-url")
+                .global(true)
+                .value_name("URL")
+                .help("API host (overrides PLANE_API_URL / PLANE_BASE_URL host)"),
+        )
+        .arg(
+            Arg::new("base-path")
+                .long("base-path")
+                .global(true)
+                .value_name("PATH")
+                .help("API base path (overrides PLANE_API_BASE_PATH / PLANE_BASE_URL path)"),
+        )
+        .arg(
+            Arg::new("print-url")
+                .long("print-url")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["dry-run", "explain", "diff"])
+                .help("Print the fully resolved URL (with query string) and exit without sending"),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print the resolved request as JSON without sending"),
+        )
         .arg(
             Arg::new("pretty")
                 .long("pretty")
@@ -94,6 +332,53 @@ fn build_cli(tree: &CommandTree) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Pretty-print JSON output"),
         )
+        .arg(
+            Arg::new("compact")
+                .long("compact")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("pretty")
+                .help("Pretty-print JSON but keep short arrays/objects on one line"),
+        )
+        .arg(
+            Arg::new("require-results")
+                .long("require-results")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Exit non-zero if the body is null, an empty array, or empty `results`"),
+        )
+        .arg(
+            Arg::new("raw-scalar")
+                .long("raw-scalar")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print a bare string/number/bool body unquoted (null as empty); others stay JSON"),
+        )
+        .arg(
+            Arg::new("flatten")
+                .long("flatten")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Table/CSV/TSV: one dotted column per nested leaf (state.name, labels.0.name)"),
+        )
+        .arg(
+            Arg::new("flatten-depth")
+                .long("flatten-depth")
+                .global(true)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .requires("flatten")
+                .help("Levels of nesting --flatten expands; deeper values stay JSON (default: 3)"),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .global(true)
+                .value_name("WHEN")
+                .value_parser(["auto", "always", "never"])
+                .default_value("auto")
+                .help("Colorize --pretty JSON output"),
+        )
         .arg(
             Arg::new("raw")
                 .long("raw")
@@ -101,6 +386,98 @@ fn build_cli(tree: &CommandTree) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Return full response with status + headers"),
         )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["raw", "quiet", "dry-run", "print-url", "output-dir"])
+                .help("Print the request as sent, the full response, and timing_ms as one object"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Suppress response body; exit code reflects status"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print diagnostics to stderr"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .global(true)
+                .value_name("FORMAT")
+                .value_parser(["json", "table", "csv", "tsv"])
+                .help("Response body format [default: json, or table with --columns]"),
+        )
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .global(true)
+                .value_name("PATH:HEADER,...")
+                .help("Table/CSV columns, e.g. id:ID,name:Name,state.name:State"),
+        )
+        .arg(
+            Arg::new("template")
+                .long("template")
+                .global(true)
+                .value_name("HANDLEBARS")
+                .conflicts_with("template-file")
+                .help("Render response body through a Handlebars template"),
+        )
+        .arg(
+            Arg::new("template-file")
+                .long("template-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Render response body through a Handlebars template file"),
+        )
+        .arg(
+            Arg::new("output-file")
+                .long("output-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Write response body to file instead of stdout (- for stdout)"),
+        )
+        .arg(
+            Arg::new("clipboard")
+                .long("clipboard")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Also copy the formatted output to the clipboard"),
+        )
+        .arg(
+            Arg::new("show-status")
+                .long("show-status")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print the HTTP status line to stderr"),
+        )
+        .arg(
+            Arg::new("no-newline")
+                .long("no-newline")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Omit trailing newline after response body"),
+        )
+        .arg(
+            Arg::new("error-format")
+                .long("error-format")
+                .global(true)
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("Error output format on stderr"),
+        )
         .arg(
             Arg::new("include-deprecated")
                 .long("include-deprecated")
@@ -116,12 +493,49 @@ fn build_cli(tree: &CommandTree) -> Command {
                 .value_name("KEY=VALUE")
                 .help("Append query parameter (repeatable)"),
         )
+        .arg(
+            Arg::new("metrics-file")
+                .long("metrics-file")
+                .global(true)
+                .value_name("PATH")
+                .help("After the run, write request/retry/byte/status counts in Prometheus text format"),
+        )
+        .arg(
+            Arg::new("query-file")
+                .long("query-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Read KEY=VALUE query lines from a file (# comments); sent before --query"),
+        )
+        .arg(
+            Arg::new("workspace")
+                .long("workspace")
+                .global(true)
+                .value_name("SLUG")
+                .help("Workspace slug for workspace path params (overrides PLANE_WORKSPACE)"),
+        )
+        .arg(
+            Arg::new("strict-query")
+                .long("strict-query")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Fail on query params the operation does not declare"),
+        )
         .arg(
             Arg::new("fields")
                 .long("fields")
                 .global(true)
+                .action(ArgAction::Append)
+                .value_name("FIELDS")
+                .help("Comma-separated response fields (repeatable)"),
+        )
+        .arg(
+            Arg::new("select")
+                .long("select")
+                .global(true)
+                .action(ArgAction::Append)
                 .value_name("FIELDS")
-                .help("Comma-separated response fields"),
+                .help("Keep only these response fields locally (comma-separated, dotted; repeatable)"),
         )
         .arg(
             Arg::new("expand")
@@ -138,6 +552,23 @@ fn build_cli(tree: &CommandTree) -> Command {
                 .value_name("N")
                 .help("Pagination: per_page"),
         )
+        .arg(
+            Arg::new("order-by")
+                .long("order-by")
+                .global(true)
+                .value_name("FIELD")
+                .allow_hyphen_values(true)
+                .help("Sort field, sent as order_by (see --order)"),
+        )
+        .arg(
+            Arg::new("order")
+                .long("order")
+                .global(true)
+                .value_name("DIR")
+                .value_parser(["asc", "desc"])
+                .requires("order-by")
+                .help("Sort direction for --order-by [default: asc]"),
+        )
         .arg(
             Arg::new("cursor")
                 .long("cursor")
@@ -146,276 +577,1687 @@ fn build_cli(tree: &CommandTree) -> Command {
                 .help("Pagination: cursor"),
         )
         .arg(
-            Arg::new("body-json")
-                .long("body-json")
+            Arg::new("all")
+                .long("all")
                 .global(true)
-                .value_name("JSON")
-                .help("JSON body payload"),
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["cursor", "pages"])
+                .help("Pagination: follow next_cursor and combine all results"),
         )
         .arg(
-            Arg::new("body-file")
-                .long("body-file")
+            Arg::new("pages")
+                .long("pages")
+                .global(true)
+                .value_name("START:END")
+                .conflicts_with("cursor")
+                .help("Pagination: combine results from pages START..=END"),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Show where each param, query pair, and the body came from; do not send"),
+        )
+        .arg(
+            Arg::new("if-match")
+                .long("if-match")
+                .global(true)
+                .value_name("ETAG|auto")
+                .help("Send If-Match on PUT/PATCH; `auto` GETs the current ETag first"),
+        )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Show what a PUT/PATCH body would change on the remote resource; don't send"),
+        )
+        .arg(
+            Arg::new("exit-on-diff")
+                .long("exit-on-diff")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .requires("diff")
+                .help("With --diff, exit non-zero when there are differences"),
+        )
+        .arg(
+            Arg::new("cursor-file")
+                .long("cursor-file")
                 .global(true)
                 .value_name("PATH")
-                .help("JSON body payload from file"),
-        );
-
-    cmd = cmd.subcommand(
-        Command::new("list")
-            .about("List resources and operations")
-            .arg(
-                Arg::new("json")
-                    .long("json")
-                    .action(ArgAction::SetTrue)
-                    .help("Emit machine-readable JSON"),
-            ),
-    );
-
-    cmd = cmd.subcommand(
-        Command::new("describe")
-            .about("Describe a specific operation")
-            .arg(Arg::new("resource").required(true))
-            .arg(Arg::new("op").required(true))
-            .arg(
-                Arg::new("json")
-                    .long("json")
-                    .action(ArgAction::SetTrue)
-                    .help("Emit machine-readable JSON"),
-            ),
-    );
-
-    cmd = cmd.subcommand(
-        Command::new("tree").about("Show full command tree").arg(
-            Arg::new("json")
-                .long("json")
+                .requires("all")
+                .help("With --all, save the next cursor after each page and resume from it"),
+        )
+        .arg(
+            Arg::new("output-dir")
+                .long("output-dir")
+                .global(true)
+                .value_name("DIR")
+                .conflicts_with("output-file")
+                .help("With --all/--pages, write each page to DIR/page-NNNN.json"),
+        )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .global(true)
+                .value_name("PATH")
+                .help("DELETE each id listed in PATH (one per line, - for stdin)"),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .global(true)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("4")
+                .help("Requests in flight for --batch and export"),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .global(true)
                 .action(ArgAction::SetTrue)
-                .help("Emit machine-readable JSON"),
-        ),
-    );
-
-    cmd = cmd.subcommand(
-        Command::new("request")
-            .about("Raw request escape hatch")
-            .arg(Arg::new("method").required(true))
-            .arg(Arg::new("path").required(true)),
-    );
+                .help("Skip confirmation prompts"),
+        )
+        .arg(
+            Arg::new("fail-fast")
+                .long("fail-fast")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Stop at the first failure (apply, --batch)"),
+        )
+        .arg(
+            Arg::new("idempotency-key")
+                .long("idempotency-key")
+                .global(true)
+                .value_name("KEY")
+                .help("Send Idempotency-Key header"),
+        )
+        .arg(
+            Arg::new("auto-idempotency")
+                .long("auto-idempotency")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("idempotency-key")
+                .help("Generate a UUID Idempotency-Key for POST requests"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .global(true)
+                .value_name("DURATION")
+                .help("Total time per request attempt, e.g. 30s, 500ms, 2m [default: 30s]"),
+        )
+        .arg(
+            Arg::new("connect-timeout")
+                .long("connect-timeout")
+                .global(true)
+                .value_name("DURATION")
+                .help("Time to establish a connection, within --timeout [default: --timeout]"),
+        )
+        .arg(
+            Arg::new("http1")
+                .long("http1")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("http2-prior-knowledge")
+                .help("Only use HTTP/1.1"),
+        )
+        .arg(
+            Arg::new("http2-prior-knowledge")
+                .long("http2-prior-knowledge")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Use HTTP/2 without negotiation"),
+        )
+        .arg(
+            Arg::new("retry")
+                .long("retry")
+                .global(true)
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32))
+                .help("Retry 429/5xx responses and connect/timeout errors up to N times"),
+        )
+        .arg(
+            Arg::new("max-response-size")
+                .long("max-response-size")
+                .global(true)
+                .value_name("BYTES")
+                .help("Fail on response bodies larger than this (e.g. 64MB; 0 = no limit) [default: 512MB]"),
+        )
+        .arg(
+            Arg::new("retry-on")
+                .long("retry-on")
+                .global(true)
+                .value_name("CODES")
+                .help("Statuses that trigger a retry, e.g. 429,500-504,522 [default: 429,500,502,503,504]"),
+        )
+        .arg(
+            Arg::new("min-interval")
+                .long("min-interval")
+                .global(true)
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Wait at least MS milliseconds between the starts of consecutive requests"),
+        )
+        .arg(
+            Arg::new("retry-timeout")
+                .long("retry-timeout")
+                .global(true)
+                .value_name("DURATION")
+                .help("Stop retrying once DURATION has passed since the first attempt"),
+        )
+        .arg(
+            Arg::new("timings")
+                .long("timings")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print per-request timings to stderr (min/median/max summary for --all)"),
+        )
+        .arg(
+            Arg::new("method-override")
+                .long("method-override")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Send PATCH/PUT/DELETE as POST with X-HTTP-Method-Override"),
+        )
+        .arg(
+            Arg::new("header")
+                .long("header")
+                .short('H')
+                .global(true)
+                .value_name("NAME: VALUE")
+                .action(ArgAction::Append)
+                .help("Extra request header (repeatable; overrides config.toml headers)"),
+        )
+        .arg(
+            Arg::new("header-file")
+                .long("header-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Read `Name: Value` header lines from a file (# comments); --header still wins"),
+        )
+        .arg(
+            Arg::new("user-agent")
+                .long("user-agent")
+                .global(true)
+                .value_name("STRING")
+                .help("User-Agent (overrides PLANE_USER_AGENT; prefix + to append)"),
+        )
+        .arg(
+            Arg::new("accept")
+                .long("accept")
+                .global(true)
+                .value_name("MIME")
+                .help("Accept header [default: application/json, */* with --download]"),
+        )
+        .arg(
+            Arg::new("compress")
+                .long("compress")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Gzip large JSON request bodies (Content-Encoding: gzip)"),
+        )
+        .arg(
+            Arg::new("no-progress")
+                .long("no-progress")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Disable the stderr progress indicator for --all/--pages"),
+        )
+        .arg(
+            Arg::new("body-json")
+                .long("body-json")
+                .global(true)
+                .value_name("JSON")
+                .help("JSON body payload"),
+        )
+        .arg(
+            Arg::new("body-file")
+                .long("body-file")
+                .global(true)
+                .value_name("PATH")
+                .help("JSON body payload from file"),
+        )
+        .arg(
+            Arg::new("no-body")
+                .long("no-body")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Send no request body"),
+        )
+        .arg(
+            Arg::new("field")
+                .long("field")
+                .global(true)
+                .action(ArgAction::Append)
+                .value_name("KEY=VALUE")
+                .help("Body field; dotted keys nest, numbers/booleans/null are typed (repeatable)"),
+        )
+        .arg(
+            Arg::new("field-json")
+                .long("field-json")
+                .global(true)
+                .action(ArgAction::Append)
+                .value_name("KEY=JSON")
+                .help("Body field with a raw JSON value (repeatable)"),
+        )
+        .arg(
+            Arg::new("body-template")
+                .long("body-template")
+                .global(true)
+                .value_name("PATH")
+                .help("JSON body from a minijinja template (variables via --var)"),
+        )
+        .arg(
+            Arg::new("var")
+                .long("var")
+                .global(true)
+                .action(ArgAction::Append)
+                .value_name("KEY=VALUE")
+                .requires("body-template")
+                .help("Template variable for --body-template (repeatable)"),
+        )
+        .arg(
+            Arg::new("body-base64-file")
+                .long("body-base64-file")
+                .global(true)
+                .value_name("PATH")
+                .help("Send a file base64-encoded as the body"),
+        )
+        .arg(
+            Arg::new("body-base64-field")
+                .long("body-base64-field")
+                .global(true)
+                .value_name("NAME")
+                .requires("body-base64-file")
+                .help("Wrap the base64 body as {NAME: ...}"),
+        );
+
+    cmd = cmd.subcommand(
+        Command::new("list")
+            .about("List resources and operations")
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Emit machine-readable JSON"),
+            )
+            .arg(
+                Arg::new("by-path")
+                    .long("by-path")
+                    .action(ArgAction::SetTrue)
+                    .help("Group operations by path and show the methods on each"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("describe")
+            .about("Describe a specific operation")
+            .arg(Arg::new("resource").required(true))
+            .arg(Arg::new("op").required(true))
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Emit machine-readable JSON"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("docs")
+            .about("Open the Plane API docs for an operation")
+            .arg(Arg::new("resource").required(true))
+            .arg(Arg::new("op").required(true))
+            .arg(
+                Arg::new("print-url")
+                    .long("print-url")
+                    .action(ArgAction::SetTrue)
+                    .help("Print the docs URL instead of opening a browser"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("tree").about("Show full command tree").arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Emit machine-readable JSON"),
+        ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("manifest").about("Emit a stable, versioned JSON catalog of all operations"),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("schema")
+            .about("Emit one operation's param/query contract as stable JSON")
+            .arg(Arg::new("resource").required(true))
+            .arg(Arg::new("op").required(true)),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("request")
+            .about("Raw request escape hatch")
+            .arg(Arg::new("method").required(true))
+            .arg(Arg::new("path").required(true))
+            .arg(Arg::new("body").help("Inline JSON body (--body-file takes precedence)"))
+            .arg(download_arg())
+            .arg(
+                Arg::new("absolute-path")
+                    .long("absolute-path")
+                    .action(ArgAction::SetTrue)
+                    .help("Treat a leading-slash path as host-relative (skip the base path)"),
+            )
+            .arg(
+                Arg::new("stream-body")
+                    .long("stream-body")
+                    .value_name("PATH")
+                    .conflicts_with_all(["body", "download"])
+                    .help("Upload PATH (- for stdin) as the body without buffering it; no retries"),
+            )
+            .arg(
+                Arg::new("content-type")
+                    .long("content-type")
+                    .value_name("TYPE")
+                    .requires("stream-body")
+                    .help("Content-Type for --stream-body (default: application/json)"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("exec")
+            .about(
+                "Run one operation from a JSON {resource, op, path_params, query, body} document",
+            )
+            .arg(
+                Arg::new("stdin")
+                    .long("stdin")
+                    .action(ArgAction::SetTrue)
+                    .required(true)
+                    .help("Read the request document from stdin"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("export")
+            .about("Back up a workspace: every list operation, all pages, one JSON file each")
+            .arg(
+                Arg::new("dir")
+                    .long("dir")
+                    .value_name("PATH")
+                    .required(true)
+                    .help("Directory for <resource>.json files"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("apply")
+            .about("Send one request per JSON file (operation from `_op` or --op)")
+            .arg(
+                Arg::new("file")
+                    .short('f')
+                    .long("file")
+                    .value_name("PATH")
+                    .action(ArgAction::Append)
+                    .required(true)
+                    .help("JSON file, directory of *.json, or glob (repeatable)"),
+            )
+            .arg(
+                Arg::new("op")
+                    .long("op")
+                    .value_name("\"RESOURCE OP\"")
+                    .help("Operation for files without an `_op` key, e.g. \"work-item create\""),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("doctor").about("Check API key, base URL, connectivity, and auth"),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("whoami")
+            .about("Show the user for the configured API key")
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Emit machine-readable JSON"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("alias")
+            .about("User-defined aliases from ~/.config/plane/aliases.toml")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("list").about("List aliases").arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Emit machine-readable JSON"),
+                ),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Run an alias (same as `plane :NAME`)")
+                    .arg(Arg::new("name").required(true))
+                    .arg(Arg::new("args").num_args(0..).trailing_var_arg(true)),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("config")
+            .about("Inspect ~/.config/plane/config.toml")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("show").about("Print --profile's settings with inheritance applied"),
+            )
+            .subcommand(
+                Command::new("fields")
+                    .about("Field presets for --fields @NAME / --select @NAME")
+                    .subcommand_required(true)
+                    .subcommand(Command::new("list").about("List [fields] presets")),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("login")
+            .about("Store API key in the OS keyring (reads stdin unless --api-key*)"),
+    );
+
+    cmd = cmd.subcommand(Command::new("logout").about("Remove API key from the OS keyring"));
+
+    let reserved: Vec<String> = cmd
+        .get_arguments()
+        .filter_map(|arg| arg.get_long().map(str::to_string))
+        .collect();
 
     for resource in &tree.resources {
         let mut res_cmd = Command::new(resource.name.clone())
             .about(resource.name.clone())
+            .infer_subcommands(true)
             .subcommand_required(true)
             .arg_required_else_help(true);
         for op in &resource.ops {
             let mut op_cmd = Command::new(op.name.clone())
                 .about(format!("{} {}", op.method, op.path));
-            if op.deprecated {
+            if op.deprecated && !show_deprecated {
                 op_cmd = op_cmd.hide(true);
             }
+            let batch_id = batch::id_param(op).map(|param| param.name.as_str());
             for param in &op.params {
-                op_cmd = op_cmd.arg(build_param_arg(param));
+                let mut arg = build_param_arg(param);
+                if batch_id == Some(param.name.as_str()) && arg.is_required_set() {
+                    arg = arg.required(false).required_unless_present("batch");
+                }
+                op_cmd = op_cmd.arg(arg);
+            }
+            if op.downloads() {
+                op_cmd = op_cmd.arg(download_arg());
+            }
+            for query in op.query.iter().filter(|query| query.is_bool()) {
+                let flag = query.flag();
+                let taken = reserved.contains(&flag) || op.params.iter().any(|p| p.flag == flag);
+                if !taken {
+                    op_cmd = op_cmd.arg(build_bool_query_arg(query));
+                }
             }
             res_cmd = res_cmd.subcommand(op_cmd);
         }
-        cmd = cmd.subcommand(res_cmd);
+        cmd = cmd.subcommand(res_cmd);
+    }
+
+    cmd
+}
+
+fn download_arg() -> Arg {
+    Arg::new("download")
+        .long("download")
+        .value_name("PATH")
+        .help("Stream response body to file (skips JSON parsing)")
+}
+
+fn build_param_arg(param: &Param) -> Arg {
+    let mut arg = Arg::new(param.name.clone())
+        .long(param.flag.clone())
+        .value_name(param.name.clone());
+    if !uses_workspace_default(param) && !is_project_param(&param.name) {
+        arg = arg.required(true);
+    }
+    arg
+}
+
+/// `--is-active` sends `is_active=true`; `--is-active=false` sends `false`.
+fn build_bool_query_arg(query: &QueryParam) -> Arg {
+    Arg::new(bool_query_id(query))
+        .long(query.flag())
+        .value_name("BOOL")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("true")
+        .value_parser(["true", "false"])
+        .help(format!("Query filter: {}", query.name))
+}
+
+fn bool_query_id(query: &QueryParam) -> String {
+    format!("query:{}", query.name)
+}
+
+/// Operations sharing a path template, sorted by path then method.
+fn ops_by_path(
+    tree: &CommandTree,
+    include_deprecated: bool,
+) -> BTreeMap<&str, Vec<(&str, &Operation)>> {
+    let mut paths: BTreeMap<&str, Vec<(&str, &Operation)>> = BTreeMap::new();
+    for res in &tree.resources {
+        for op in res
+            .ops
+            .iter()
+            .filter(|op| include_deprecated || !op.deprecated)
+        {
+            paths
+                .entry(op.path.as_str())
+                .or_default()
+                .push((res.name.as_str(), op));
+        }
+    }
+    for ops in paths.values_mut() {
+        ops.sort_by(|a, b| a.1.method.cmp(&b.1.method));
+    }
+    paths
+}
+
+fn handle_list_by_path(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let paths = ops_by_path(tree, matches.get_flag("include-deprecated"));
+    if matches.get_flag("json") {
+        let out: Vec<Value> = paths
+            .iter()
+            .map(|(path, ops)| {
+                let methods: Vec<&str> = ops.iter().map(|(_, op)| op.method.as_str()).collect();
+                let ops: Vec<Value> = ops
+                    .iter()
+                    .map(|(res, op)| json!({"resource": res, "op": op.name, "method": op.method}))
+                    .collect();
+                json!({"path": path, "methods": methods, "ops": ops})
+            })
+            .collect();
+        return write_output(&Value::Array(out), true);
+    }
+    for (path, ops) in &paths {
+        let methods: Vec<&str> = ops.iter().map(|(_, op)| op.method.as_str()).collect();
+        write_stdout_line(&format!("{path}  [{}]", methods.join(" ")))?;
+        for (res, op) in ops {
+            write_stdout_line(&format!("  {:<6} {res} {}", op.method, op.name))?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_list(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    if matches.get_flag("by-path") {
+        return handle_list_by_path(tree, matches);
+    }
+    let include_deprecated = matches.get_flag("include-deprecated");
+    if matches.get_flag("json") {
+        let mut out = Vec::new();
+        for res in &tree.resources {
+            let ops: Vec<Value> = res
+                .ops
+                .iter()
+                .filter(|op| include_deprecated || !op.deprecated)
+                .map(|op| {
+                    json!({
+                        "name": op.name,
+                        "method": op.method,
+                        "path": op.path,
+                        "deprecated": op.deprecated,
+                    })
+                })
+                .collect();
+            out.push(json!({"resource": res.name, "ops": ops}));
+        }
+        write_output(&Value::Array(out), true)?;
+        return Ok(());
+    }
+
+    for res in &tree.resources {
+        write_stdout_line(&res.name)?;
+        for op in &res.ops {
+            if op.deprecated && !include_deprecated {
+                continue;
+            }
+            let suffix = if op.deprecated { " (deprecated)" } else { "" };
+            write_stdout_line(&format!("  {} {}{}", op.name, op.method, suffix))?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let resource = matches
+        .get_one::<String>("resource")
+        .ok_or_else(|| anyhow!("resource required"))?;
+    let op_name = matches
+        .get_one::<String>("op")
+        .ok_or_else(|| anyhow!("operation required"))?;
+
+    let op = find_op(tree, resource, op_name)?;
+
+    if matches.get_flag("json") {
+        write_output(&serde_json::to_value(op)?, true)?;
+        return Ok(());
+    }
+
+    write_stdout_line(&format!("{} {}", resource, op.name))?;
+    write_stdout_line(&format!("  method: {}", op.method))?;
+    write_stdout_line(&format!("  path: {}", op.path))?;
+    write_stdout_line(&format!("  deprecated: {}", op.deprecated))?;
+    if let Some(accept) = &op.default_accept {
+        write_stdout_line(&format!("  accept: {accept}"))?;
+    }
+    if let Some(range) = api_version_range(op) {
+        write_stdout_line(&format!("  api versions: {range}"))?;
+    }
+    if !op.expand.is_empty() {
+        write_stdout_line(&format!("  expand: {}", op.expand.join(", ")))?;
+    }
+    if let Some(siblings) = ops_by_path(tree, true).get(op.path.as_str())
+        && siblings.len() > 1
+    {
+        let others: Vec<String> = siblings
+            .iter()
+            .map(|(res, sibling)| format!("{} {res} {}", sibling.method, sibling.name))
+            .collect();
+        write_stdout_line(&format!("  same path: {}", others.join(", ")))?;
+    }
+    if !op.params.is_empty() {
+        write_stdout_line("  params:")?;
+        for param in &op.params {
+            write_stdout_line(&format!("    --{}", param.flag))?;
+        }
+    }
+    if !op.default_query.is_empty() {
+        let defaults: Vec<String> = op
+            .default_query
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        write_stdout_line(&format!("  default query: {}", defaults.join("&")))?;
+    }
+    Ok(())
+}
+
+fn api_version_range(op: &Operation) -> Option<String> {
+    match (&op.min_api_version, &op.max_api_version) {
+        (None, None) => None,
+        (Some(min), None) => Some(format!(">= {min}")),
+        (None, Some(max)) => Some(format!("<= {max}")),
+        (Some(min), Some(max)) => Some(format!("{min} - {max}")),
+    }
+}
+
+fn handle_schema(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let resource = matches
+        .get_one::<String>("resource")
+        .ok_or_else(|| anyhow!("resource required"))?;
+    let op_name = matches
+        .get_one::<String>("op")
+        .ok_or_else(|| anyhow!("operation required"))?;
+    let op = find_op(tree, resource, op_name)?;
+    let resource = resource_name(tree, resource);
+
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+    let example_url = join_url(&api_url, &base_path, &placeholder_path(op)?);
+    write_output(
+        &serde_json::to_value(manifest::schema(resource, op, example_url))?,
+        true,
+    )
+}
+
+/// Path with each `<type:name>` token replaced by `{name}`.
+fn placeholder_path(op: &Operation) -> Result<String> {
+    let placeholders = op
+        .params
+        .iter()
+        .map(|param| (param.name.clone(), format!("{{{}}}", param.name)))
+        .collect();
+    build_path(op, &placeholders)
+}
+
+const DOCS_BASE_URL: &str = "https://developers.plane.so/api-reference";
+
+fn handle_docs(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let resource = matches
+        .get_one::<String>("resource")
+        .ok_or_else(|| anyhow!("resource required"))?;
+    let op_name = matches
+        .get_one::<String>("op")
+        .ok_or_else(|| anyhow!("operation required"))?;
+    let op = find_op(tree, resource, op_name)?;
+    let url = docs_url(resource, op);
+
+    if matches.get_flag("print-url") {
+        return write_stdout_line(&url);
+    }
+    if let Err(err) = webbrowser::open(&url) {
+        eprintln!("warning: could not open browser: {err}");
+        return write_stdout_line(&url);
+    }
+    Ok(())
+}
+
+/// Uses the op's `docs_url` when the tree has one, else the resource's API reference page.
+fn docs_url(resource: &str, op: &Operation) -> String {
+    if let Some(url) = &op.docs_url {
+        return url.clone();
+    }
+    format!("{DOCS_BASE_URL}/{resource}/overview")
+}
+
+fn handle_tree(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    if matches.get_flag("json") {
+        write_output(&serde_json::to_value(tree)?, true)?;
+        return Ok(());
+    }
+    write_stdout_line("Run with --json for machine-readable output.")?;
+    Ok(())
+}
+
+fn handle_request(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+
+    let method = &http::normalize_method(
+        matches
+            .get_one::<String>("method")
+            .ok_or_else(|| anyhow!("method required"))?,
+    );
+    let path = matches
+        .get_one::<String>("path")
+        .ok_or_else(|| anyhow!("path required"))?;
+
+    let url = request_url(
+        &api_url,
+        &base_path,
+        path,
+        matches.get_flag("absolute-path"),
+    );
+
+    let query = build_query_params(matches, &read_query_file(matches)?)?;
+    if let Some(source) = matches.get_one::<String>("stream-body") {
+        let target = Target {
+            api_url: &api_url,
+            base_path: &base_path,
+            method,
+            url: &url,
+            accept: None,
+        };
+        return handle_stream_upload(matches, &target, &query, source);
+    }
+    let body = request_body(matches)?;
+
+    let target = Target {
+        api_url: &api_url,
+        base_path: &base_path,
+        method,
+        url: &url,
+        accept: None,
+    };
+    match matches.get_one::<String>("download") {
+        Some(dest) if !matches.get_flag("dry-run") && !matches.get_flag("print-url") => {
+            handle_download(matches, &target, &query, body, dest)
+        }
+        _ => dispatch(matches, &target, &query, body),
+    }
+}
+
+/// `request --stream-body`: the file (or stdin) goes to the server as it is read,
+/// for imports too large to hold in memory as a JSON value.
+fn handle_stream_upload(
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    source: &str,
+) -> Result<()> {
+    let body_flags = [
+        "body-json",
+        "body-file",
+        "body-base64-file",
+        "body-template",
+        "field",
+        "field-json",
+    ];
+    let conflicting = body_flags
+        .into_iter()
+        .find(|id| matches.contains_id(id))
+        .or(matches.get_flag("no-body").then_some("no-body"));
+    if let Some(id) = conflicting {
+        return Err(anyhow!("--stream-body cannot be combined with --{id}"));
+    }
+    let content_type = matches
+        .get_one::<String>("content-type")
+        .map_or("application/json", String::as_str);
+    if matches.get_flag("print-url") {
+        return dispatch(matches, target, query, None);
+    }
+    if matches.get_flag("dry-run") {
+        eprintln!("dry-run: body would be streamed from {source} as {content_type}");
+        return dispatch(matches, target, query, None);
+    }
+    let body = stream_source(source)?;
+    let client = build_client(matches, target.method)?;
+    let response = client.send_stream(target.method, target.url, query, body, content_type)?;
+    emit_response(matches, target.url, response)
+}
+
+fn stream_source(source: &str) -> Result<reqwest::blocking::Body> {
+    if source == "-" {
+        return Ok(reqwest::blocking::Body::new(std::io::stdin()));
+    }
+    let file = fs::File::open(source).with_context(|| format!("open --stream-body {source}"))?;
+    Ok(reqwest::blocking::Body::from(file))
+}
+
+/// `request METHOD PATH [BODY]`: the positional is an inline `--body-json`, but
+/// `--body-file` overrides it.
+fn request_body(matches: &clap::ArgMatches) -> Result<Option<Value>> {
+    let Some(raw) = matches.get_one::<String>("body") else {
+        return read_body(matches);
+    };
+    if matches.contains_id("body-file") {
+        return read_body(matches);
+    }
+    if matches.get_flag("no-body") {
+        return Err(anyhow!("--no-body cannot be combined with a body flag"));
+    }
+    let conflicting = ["body-json", "body-base64-file", "field", "field-json"]
+        .into_iter()
+        .find(|id| matches.contains_id(id));
+    if let Some(id) = conflicting {
+        return Err(anyhow!("use either the BODY argument or --{id}, not both"));
+    }
+    serde_json::from_str(raw)
+        .map(Some)
+        .with_context(|| format!("BODY argument is not valid JSON: {raw}"))
+}
+
+/// Absolute URLs are used as-is. Other paths get the base path, unless
+/// `absolute_path` is set and the path starts with `/` (host-relative).
+fn request_url(api_url: &str, base_path: &str, path: &str, absolute_path: bool) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else if absolute_path && path.starts_with('/') {
+        format!("{}{}", api_url.trim_end_matches('/'), path)
+    } else {
+        join_url(api_url, base_path, path)
+    }
+}
+
+fn handle_download(
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    body: Option<Value>,
+    dest: &str,
+) -> Result<()> {
+    let mut client = build_client(matches, target.method)?;
+    if !matches.contains_id("accept") {
+        client = client.with_accept(target.accept.unwrap_or("*/*"));
+    }
+    let dest = std::path::Path::new(dest);
+    match client.download(target.method, target.url, query, body, dest)? {
+        Download::Saved(download) => {
+            let content_type = download.content_type.as_deref().unwrap_or("unknown");
+            eprintln!(
+                "downloaded {} bytes ({content_type}, http {}) to {}",
+                download.bytes,
+                download.status,
+                dest.display()
+            );
+            Ok(())
+        }
+        Download::Failed(response) => emit_response(matches, target.url, response),
+    }
+}
+
+/// `--print-url`: the URL as sent, query string encoded the way reqwest does it.
+fn resolved_url(url: &str, query: &[(String, String)]) -> Result<String> {
+    let url = if query.is_empty() {
+        reqwest::Url::parse(url)
+    } else {
+        reqwest::Url::parse_with_params(url, query)
+    };
+    Ok(url.with_context(|| "invalid request URL")?.to_string())
+}
+
+/// Fully resolved destination of a request, shared by generated ops and `request`.
+struct Target<'a> {
+    api_url: &'a str,
+    base_path: &'a str,
+    method: &'a str,
+    url: &'a str,
+    /// The operation's `default_accept`, used unless `--accept` is given.
+    accept: Option<&'a str>,
+}
+
+fn dispatch(
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    body: Option<Value>,
+) -> Result<()> {
+    if matches.get_flag("print-url") {
+        return write_stdout_line(&resolved_url(target.url, query)?);
+    }
+    if matches.get_flag("dry-run") {
+        let mut headers = extra_headers(matches)?;
+        if let Some(etag) = matches.get_one::<String>("if-match") {
+            // `auto` is shown as-is; the ETag is only fetched when sending.
+            config::set_header(&mut headers, "If-Match", etag);
+        }
+        let plan = json!({
+            "method": target.method,
+            "url": target.url,
+            "api_url": target.api_url,
+            "base_path": target.base_path,
+            "query": query,
+            "headers": config::redact_headers(&headers),
+            "body": body,
+        });
+        return write_output(&plan, matches.get_flag("pretty"));
+    }
+
+    let mut client = build_client(matches, target.method)?;
+    if !matches.contains_id("accept")
+        && let Some(accept) = target.accept
+    {
+        client = client.with_accept(accept);
+    }
+    let etag = if_match(&client, matches, target)?;
+    if let Some(etag) = &etag {
+        client = client.with_header("If-Match", etag.as_str());
+    }
+    if let Some(dir) = matches.get_one::<String>("output-dir") {
+        return save_pages(&client, matches, target, query, body, dir);
+    }
+    if matches.get_flag("trace") {
+        return trace(&client, matches, target, query, body);
+    }
+    let response = send(&client, matches, target.method, target.url, query, body)?;
+    if response.status == 412 && etag.is_some() {
+        return Err(anyhow!(
+            "resource changed since read (HTTP 412 Precondition Failed); fetch it again and retry"
+        ));
+    }
+    emit_response(matches, target.url, response)?;
+    interrupt::check()
+}
+
+/// `--trace`: sends the request and prints it (headers redacted) with the response.
+fn trace(
+    client: &HttpClient,
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    body: Option<Value>,
+) -> Result<()> {
+    let headers = client.request_headers(target.method, target.url, query, body.as_ref())?;
+    let request = json!({
+        "method": target.method,
+        "url": resolved_url(target.url, query)?,
+        "query": query,
+        "headers": config::redact_headers(&headers)
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect::<Map<_, _>>(),
+        "body": body.clone(),
+    });
+    let started = std::time::Instant::now();
+    let response = send(client, matches, target.method, target.url, query, body)?;
+    let status = ensure_success(response.status, target.url, &response.body);
+    let envelope = trace_envelope(request, response, started.elapsed());
+    write_output(&envelope, matches.get_flag("pretty"))?;
+    status
+}
+
+fn trace_envelope(request: Value, response: ResponseData, elapsed: std::time::Duration) -> Value {
+    json!({
+        "request": request,
+        "response": {
+            "status": response.status,
+            "headers": response.headers,
+            "body": response.body,
+        },
+        "timing_ms": elapsed.as_millis() as u64,
+    })
+}
+
+/// `--if-match ETAG` as given; `--if-match auto` GETs the target first for its `ETag`.
+fn if_match(
+    client: &HttpClient,
+    matches: &clap::ArgMatches,
+    target: &Target,
+) -> Result<Option<String>> {
+    let Some(value) = matches.get_one::<String>("if-match") else {
+        return Ok(None);
+    };
+    if !matches!(target.method, "PUT" | "PATCH") {
+        return Err(anyhow!("--if-match only applies to PUT/PATCH"));
+    }
+    if value != "auto" {
+        return Ok(Some(value.clone()));
+    }
+    fetch_etag(client, target.url).map(Some)
+}
+
+fn fetch_etag(client: &HttpClient, url: &str) -> Result<String> {
+    let current = client.execute("GET", url, &[], None)?;
+    ensure_success(current.status, url, &current.body)?;
+    current
+        .headers
+        .get("etag")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("--if-match auto: {url} returned no ETag header"))
+}
+
+/// `--output-dir`: one file per page instead of one combined body; prints the paths.
+fn save_pages(
+    client: &HttpClient,
+    matches: &clap::ArgMatches,
+    target: &Target,
+    query: &[(String, String)],
+    body: Option<Value>,
+    dir: &str,
+) -> Result<()> {
+    let range =
+        page_range(matches)?.ok_or_else(|| anyhow!("--output-dir needs --all or --pages"))?;
+    interrupt::install();
+    let bar = progress_bar(matches);
+    let request = PageRequest {
+        method: target.method,
+        url: target.url,
+        query,
+        body,
+        stop: Some(interrupt::flag()),
+    };
+    let saved = pagination::save_pages(
+        client,
+        request,
+        range,
+        checkpoint(matches).as_ref(),
+        std::path::Path::new(dir),
+        &mut page_progress(&bar),
+    );
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+    match saved? {
+        SavedPages::Saved { files, items } => {
+            for file in &files {
+                write_stdout_line(&file.display().to_string())?;
+            }
+            eprintln!("wrote {} pages ({items} items) to {dir}", files.len());
+            interrupt::check()
+        }
+        SavedPages::Response(response) => emit_response(matches, target.url, response),
+    }
+}
+
+/// Builds the client for one logical request; per-request headers such as the
+/// idempotency key are fixed here so every attempt reuses them.
+fn build_client(matches: &clap::ArgMatches, method: &str) -> Result<HttpClient> {
+    build_client_with_key(matches, method, resolve_api_key(matches)?)
+}
+
+/// `build_client` with the API key already resolved, so worker threads and `doctor` probes
+/// look it up (and run `--api-key-command`) only once.
+fn build_client_with_key(
+    matches: &clap::ArgMatches,
+    method: &str,
+    api_key: String,
+) -> Result<HttpClient> {
+    let duration = |id: &str| {
+        matches
+            .get_one::<String>(id)
+            .map(|value| http::parse_duration(value).with_context(|| format!("--{id}")))
+            .transpose()
+    };
+    let options = ClientOptions {
+        user_agent: resolve_user_agent(matches),
+        timeout: duration("timeout")?,
+        connect_timeout: duration("connect-timeout")?,
+        http_version: http_version(matches),
+    };
+    match options.http_version {
+        HttpVersion::Auto => {}
+        HttpVersion::Http1 => verbose(matches, "forcing HTTP/1.1 (--http1)"),
+        HttpVersion::Http2PriorKnowledge => verbose(
+            matches,
+            "forcing HTTP/2 prior knowledge (--http2-prior-knowledge)",
+        ),
+    }
+    let mut client = HttpClient::new(api_key, options)?
+        .with_compression(matches.get_flag("compress"))
+        .with_retries(retry_count(matches))
+        .with_retry_timeout(duration("retry-timeout")?)
+        .with_retry_on(match matches.get_one::<String>("retry-on") {
+            Some(list) => http::parse_status_list(list).context("--retry-on")?,
+            None => http::DEFAULT_RETRY_STATUSES.to_vec(),
+        })
+        .with_verbose(matches.get_flag("verbose"))
+        .with_max_response_size(match matches.get_one::<String>("max-response-size") {
+            Some(size) => Some(http::parse_size(size).context("--max-response-size")?)
+                .filter(|limit| *limit > 0),
+            None => Some(http::DEFAULT_MAX_RESPONSE_SIZE),
+        })
+        .with_timings(matches.get_flag("timings"))
+        .with_pacer(pacer(matches))
+        .with_counters(metrics::counters())
+        .with_method_override(matches.get_flag("method-override"));
+    if let Some(accept) = matches.get_one::<String>("accept") {
+        client = client.with_accept(accept);
+    }
+    if let Some(key) = idempotency_key(matches, method) {
+        client = client.with_header("Idempotency-Key", key);
+    }
+    for (name, value) in extra_headers(matches)? {
+        client = client.with_header(name, value);
+    }
+    Ok(client)
+}
+
+/// `--min-interval`; workers that share one pass it to `HttpClient::with_pacer`.
+fn pacer(matches: &clap::ArgMatches) -> Option<http::Pacer> {
+    matches
+        .get_one::<u64>("min-interval")
+        .map(|ms| http::Pacer::new(std::time::Duration::from_millis(*ms)))
+}
+
+/// Config `[headers]` < `[profiles.<name>.headers]` < `--header`.
+fn extra_headers(matches: &clap::ArgMatches) -> Result<Vec<(String, String)>> {
+    let mut headers = config::load()?.headers(profile_name(matches));
+    if let Some(path) = matches.get_one::<String>("header-file") {
+        let raw = fs::read_to_string(path).with_context(|| format!("read --header-file {path}"))?;
+        let lines =
+            config::parse_header_lines(&raw).with_context(|| format!("--header-file {path}"))?;
+        for (name, value) in lines {
+            config::set_header(&mut headers, &name, &value);
+        }
+    }
+    if let Some(values) = matches.get_many::<String>("header") {
+        for value in values {
+            let (name, value) = config::parse_header(value)?;
+            config::set_header(&mut headers, &name, &value);
+        }
+    }
+    Ok(headers)
+}
+
+/// `--retry-timeout` alone retries until the time budget runs out.
+fn retry_count(matches: &clap::ArgMatches) -> u32 {
+    match matches.get_one::<u32>("retry") {
+        Some(retries) => *retries,
+        None if matches.get_one::<String>("retry-timeout").is_some() => u32::MAX,
+        None => 0,
+    }
+}
+
+fn http_version(matches: &clap::ArgMatches) -> HttpVersion {
+    if matches.get_flag("http1") {
+        HttpVersion::Http1
+    } else if matches.get_flag("http2-prior-knowledge") {
+        HttpVersion::Http2PriorKnowledge
+    } else {
+        HttpVersion::Auto
+    }
+}
+
+/// Diagnostic line on stderr, only with `--verbose`.
+fn verbose(matches: &clap::ArgMatches, message: impl std::fmt::Display) {
+    if matches.get_flag("verbose") {
+        eprintln!("verbose: {message}");
+    }
+}
+
+/// `--user-agent` > `PLANE_USER_AGENT` > `plane-cli/<version>`; a leading `+` appends
+/// to the default instead of replacing it.
+fn resolve_user_agent(matches: &clap::ArgMatches) -> String {
+    let value = matches
+        .get_one::<String>("user-agent")
+        .cloned()
+        .or_else(|| env::var("PLANE_USER_AGENT").ok())
+        .filter(|value| !value.trim().is_empty());
+    match value {
+        Some(value) => match value.strip_prefix('+') {
+            Some(extra) => format!("{} {}", http::default_user_agent(), extra.trim()),
+            None => value,
+        },
+        None => http::default_user_agent(),
+    }
+}
+
+fn idempotency_key(matches: &clap::ArgMatches, method: &str) -> Option<String> {
+    if let Some(key) = matches.get_one::<String>("idempotency-key") {
+        return Some(key.clone());
+    }
+    if matches.get_flag("auto-idempotency") && method.eq_ignore_ascii_case("POST") {
+        return Some(uuid::Uuid::new_v4().to_string());
+    }
+    None
+}
+
+fn send(
+    client: &HttpClient,
+    matches: &clap::ArgMatches,
+    method: &str,
+    url: &str,
+    query: &[(String, String)],
+    body: Option<Value>,
+) -> Result<ResponseData> {
+    let Some(range) = page_range(matches)? else {
+        return client.execute(method, url, query, body);
+    };
+
+    interrupt::install();
+    let bar = progress_bar(matches);
+    let request = PageRequest {
+        method,
+        url,
+        query,
+        body,
+        stop: Some(interrupt::flag()),
+    };
+    let response = pagination::fetch_pages(
+        client,
+        request,
+        range,
+        checkpoint(matches).as_ref(),
+        &mut page_progress(&bar),
+    );
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+    if let Some(summary) = client.timing_summary() {
+        eprintln!("{summary}");
+    }
+    if interrupt::requested() {
+        let items = response
+            .as_ref()
+            .ok()
+            .and_then(|response| response.body.as_array())
+            .map_or(0, Vec::len);
+        eprintln!("stopped early: {items} items collected");
+    }
+    response
+}
+
+fn checkpoint(matches: &clap::ArgMatches) -> Option<Checkpoint> {
+    matches
+        .get_one::<String>("cursor-file")
+        .map(Checkpoint::new)
+}
+
+fn page_progress(bar: &Option<ProgressBar>) -> impl FnMut(PageProgress) + '_ {
+    move |progress: PageProgress| {
+        let Some(bar) = bar else { return };
+        if let Some(total) = progress.total_count {
+            if bar.length().is_none() {
+                bar.set_style(progress_style(
+                    "{spinner} fetched {pos}/{len} items ({msg})",
+                ));
+            }
+            bar.set_length(total);
+        }
+        bar.set_position(progress.items as u64);
+        bar.set_message(format!("{} pages", progress.pages));
+    }
+}
+
+/// Spinner on stderr; `None` when stderr is not a TTY or `--no-progress` is set.
+fn progress_bar(matches: &clap::ArgMatches) -> Option<ProgressBar> {
+    if matches.get_flag("no-progress") || !std::io::stderr().is_terminal() {
+        return None;
+    }
+    let bar = ProgressBar::no_length();
+    bar.set_style(progress_style("{spinner} fetched {pos} items ({msg})"));
+    bar.enable_steady_tick(std::time::Duration::from_millis(120));
+    Some(bar)
+}
+
+fn progress_style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template).unwrap_or_else(|_| ProgressStyle::default_spinner())
+}
+
+fn page_range(matches: &clap::ArgMatches) -> Result<Option<PageRange>> {
+    if matches.get_flag("all") {
+        return Ok(Some(PageRange::all()));
+    }
+    matches
+        .get_one::<String>("pages")
+        .map(|pages| PageRange::parse(pages))
+        .transpose()
+}
+
+fn emit_response(matches: &clap::ArgMatches, url: &str, mut response: ResponseData) -> Result<()> {
+    if matches.get_flag("show-status") {
+        eprintln!("{}", status_line(response.status));
+    }
+    let status = ensure_success(response.status, url, &response.body).and_then(|()| {
+        if matches.get_flag("require-results") && output::is_empty_result(&response.body) {
+            return Err(anyhow!("no results matched"));
+        }
+        Ok(())
+    });
+    if matches.get_flag("quiet") {
+        return status;
+    }
+    // The error message already summarizes HTML error pages; only --raw prints the markup.
+    if status.is_err() && !matches.get_flag("raw") && http::looks_like_html(&response.body) {
+        return status;
+    }
+
+    let selected = selected_fields(matches)?;
+    if status.is_ok() && !selected.is_empty() {
+        response.body = output::select(&response.body, &selected);
+    }
+    let output = if matches.get_flag("raw") {
+        let mut raw = json!({
+            "status": response.status,
+            "headers": response.headers,
+        });
+        if let Some(pagination) = pagination::summary(&response.body) {
+            raw["pagination"] = pagination;
+        }
+        raw["body"] = response.body;
+        raw
+    } else {
+        response.body
+    };
+
+    let rendered = render_body(matches, &output)?;
+    write_body(matches, &rendered)?;
+    status
+}
+
+/// `HTTP 201 Created`; codes without a canonical reason print bare.
+fn status_line(status: u16) -> String {
+    let reason = reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|code| code.canonical_reason());
+    match reason {
+        Some(reason) => format!("HTTP {status} {reason}"),
+        None => format!("HTTP {status}"),
+    }
+}
+
+fn render_body(matches: &clap::ArgMatches, value: &Value) -> Result<String> {
+    if let Some(template) = matches.get_one::<String>("template") {
+        return output::render_template(template, value);
+    }
+    if let Some(path) = matches.get_one::<String>("template-file") {
+        let template = fs::read_to_string(path).context("read template file")?;
+        return output::render_template(&template, value);
+    }
+    if matches.get_flag("raw-scalar")
+        && let Some(text) = output::raw_scalar(value)
+    {
+        return Ok(text);
+    }
+
+    let pinned = matches
+        .get_one::<String>("columns")
+        .map(|spec| output::parse_columns(spec))
+        .transpose()?;
+    let format = match matches.get_one::<String>("output") {
+        Some(format) => format.as_str(),
+        None if pinned.is_some() => "table",
+        None => "json",
+    };
+    let fields = requested_fields(matches)?;
+    let columns = match pinned {
+        Some(columns) => Some(columns),
+        None if fields.is_empty() => None,
+        None => Some(output::parse_columns(&fields.join(","))?),
+    };
+    let tabular = matches!(format, "table" | "csv" | "tsv");
+    let columns = match columns {
+        None if tabular && matches.get_flag("flatten") => {
+            let depth = *matches.get_one::<usize>("flatten-depth").unwrap_or(&3);
+            Some(output::flat_columns(&output::rows(value), depth))
+        }
+        columns => columns,
+    };
+    if !tabular && matches.get_flag("flatten") {
+        verbose(matches, "--flatten only applies to table/csv/tsv output");
+    }
+    match format {
+        "table" => Ok(output::render_table(value, columns.as_deref())),
+        "csv" => output::render_delimited(value, columns.as_deref(), b','),
+        "tsv" => output::render_delimited(value, columns.as_deref(), b'\t'),
+        _ if matches.get_flag("compact") => {
+            Ok(output::render_compact(value, output::COMPACT_WIDTH))
+        }
+        _ if matches.get_flag("pretty") && use_color(matches) => Ok(output::colorize_pretty(value)),
+        _ => render_json(value, matches.get_flag("pretty")),
     }
-
-    cmd
 }
 
-fn build_param_arg(param: &Param) -> Arg {
-    let mut arg = Arg::new(param.name.clone())
-        .long(param.flag.clone())
-        .value_name(param.name.clone());
-    if !is_workspace_param(&param.name) {
-        arg = arg.required(true);
+/// `--color auto` colors only a TTY stdout (not `--output-file`) and honors `NO_COLOR`.
+fn use_color(matches: &clap::ArgMatches) -> bool {
+    match matches.get_one::<String>("color").map(String::as_str) {
+        Some("always") => true,
+        Some("never") => false,
+        _ => {
+            let to_file = matches
+                .get_one::<String>("output-file")
+                .is_some_and(|path| path != "-");
+            let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            !to_file && !no_color && std::io::stdout().is_terminal()
+        }
     }
-    arg
 }
 
-fn handle_list(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
-    let include_deprecated = matches.get_flag("include-deprecated");
-    if matches.get_flag("json") {
-        let mut out = Vec::new();
-        for res in &tree.resources {
-            let ops: Vec<Value> = res
-                .ops
-                .iter()
-                .filter(|op| include_deprecated || !op.deprecated)
-                .map(|op| {
-                    json!({
-                        "name": op.name,
-                        "method": op.method,
-                        "path": op.path,
-                        "deprecated": op.deprecated,
-                    })
-                })
-                .collect();
-            out.push(json!({"resource": res.name, "ops": ops}));
+/// Writes the rendered body to `--output-file` (creating parent dirs) or stdout.
+fn write_body(matches: &clap::ArgMatches, rendered: &str) -> Result<()> {
+    if matches.get_flag("clipboard") {
+        // Headless hosts have no clipboard; the output is still printed below.
+        if let Err(err) = clipboard::copy(rendered) {
+            eprintln!("warning: {err:#}");
         }
-        write_output(&Value::Array(out), true)?;
-        return Ok(());
     }
+    let newline = !matches.get_flag("no-newline");
+    let path = match matches.get_one::<String>("output-file") {
+        Some(path) if path != "-" => std::path::Path::new(path),
+        _ => return write_stdout(rendered, newline),
+    };
 
-    for res in &tree.resources {
-        write_stdout_line(&res.name)?;
-        for op in &res.ops {
-            if op.deprecated && !include_deprecated {
-                continue;
-            }
-            let suffix = if op.deprecated { " (deprecated)" } else { "" };
-            write_stdout_line(&format!("  {} {}{}", op.name, op.method, suffix))?;
-        }
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+    }
+    let mut contents = rendered.as_bytes().to_vec();
+    if newline {
+        contents.push(b'\n');
     }
+    fs::write(path, &contents).with_context(|| format!("write {}", path.display()))?;
+    eprintln!("wrote {} bytes to {}", contents.len(), path.display());
     Ok(())
 }
 
-fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
-    let resource = matches
-        .get_one::<String>("resource")
-        .ok_or_else(|| anyhow!("resource required"))?;
-    let op_name = matches
-        .get_one::<String>("op")
-        .ok_or_else(|| anyhow!("operation required"))?;
-
-    let op = find_op(tree, resource, op_name)
-        .ok_or_else(|| anyhow!("unknown command {resource} {op_name}"))?;
+fn handle_whoami(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let (api_url, base_path) = resolve_api_base(tree, matches)?;
+    let path = find_op(tree, "user", "me-list")
+        .map(|op| op.path.clone())
+        .unwrap_or_else(|_| "users/me/".to_string());
+    let url = join_url(&api_url, &base_path, &path);
 
-    if matches.get_flag("json") {
-        write_output(&serde_json::to_value(op)?, true)?;
-        return Ok(());
+    let client = build_client(matches, "GET")?;
+    let response = client
+        .execute("GET", &url, &[], None)
+        .map_err(|err| anyhow!("could not reach {url}: {err:#}"))?;
+    if matches!(response.status, 401 | 403) {
+        return Err(anyhow!(
+            "API key invalid or expired (http {} from {url})",
+            response.status
+        ));
     }
+    ensure_success(response.status, &url, &response.body)?;
 
-    write_stdout_line(&format!("{} {}", resource, op.name))?;
-    write_stdout_line(&format!("  method: {}", op.method))?;
-    write_stdout_line(&format!("  path: {}", op.path))?;
-    write_stdout_line(&format!("  deprecated: {}", op.deprecated))?;
-    if !op.params.is_empty() {
-        write_stdout_line("  params:")?;
-        for param in &op.params {
-            write_stdout_line(&format!("    --{}", param.flag))?;
-        }
+    if matches.get_flag("json") {
+        return write_output(&response.body, true);
     }
-    Ok(())
+    let field = |key: &str| {
+        response
+            .body
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or("-")
+            .to_string()
+    };
+    write_stdout_line(&format!("email: {}", field("email")))?;
+    write_stdout_line(&format!("id: {}", field("id")))?;
+    write_stdout_line(&format!("api: {url}"))
 }
 
-fn handle_tree(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+fn handle_alias(matches: &clap::ArgMatches) -> Result<()> {
+    let Some(matches) = matches.subcommand_matches("list") else {
+        // `alias run` is rewritten before parsing; reaching here means the alias was missing.
+        return Err(anyhow!("alias name required"));
+    };
+    let aliases = alias::load()?;
     if matches.get_flag("json") {
-        write_output(&serde_json::to_value(tree)?, true)?;
+        write_output(&serde_json::to_value(&aliases)?, true)?;
         return Ok(());
     }
-    write_stdout_line("Run with --json for machine-readable output.")?;
+    if aliases.is_empty() {
+        let path = alias::aliases_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "aliases.toml".to_string());
+        write_stdout_line(&format!("No aliases defined in {path}"))?;
+        return Ok(());
+    }
+    for (name, args) in &aliases {
+        write_stdout_line(&format!(":{name} = {}", args.join(" ")))?;
+    }
     Ok(())
 }
 
-fn handle_request(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
-    let api_key = env::var("PLANE_API_KEY").context("PLANE_API_KEY missing")?;
-    let (api_url, base_path) = resolve_api_base(&tree)?;
-
-    let method = matches
-        .get_one::<String>("method")
-        .ok_or_else(|| anyhow!("method required"))?;
-    let path = matches
-        .get_one::<String>("path")
-        .ok_or_else(|| anyhow!("path required"))?;
-
-    let url = if path.starts_with("http://") || path.starts_with("https://") {
-        path.to_string()
-    } else if path.starts_with('/') {
-        format!("{}{}", api_url.trim_end_matches('/'), path)
-    } else {
-        join_url(&api_url, &base_path, path)
+fn handle_login(matches: &clap::ArgMatches) -> Result<()> {
+    let profile = profile_name(matches);
+    let api_key = match explicit_api_key(matches)? {
+        Some(key) => key,
+        None => read_api_key_stdin()?,
     };
+    keychain::store(profile, &api_key)?;
+    write_stdout_line(&format!("Stored API key for profile {profile}"))
+}
 
-    let query = build_query_params(matches)?;
-    let body = read_body(matches)?;
-
-    let client = HttpClient::new(api_key)?;
-    let response = client.execute(method, &url, &query, body)?;
+fn handle_config(matches: &clap::ArgMatches) -> Result<()> {
+    let matches = match matches.subcommand() {
+        Some(("show", matches)) => matches,
+        Some(("fields", _)) => {
+            for (name, fields) in config::load()?.fields {
+                write_stdout_line(&format!("@{name}  {fields}"))?;
+            }
+            return Ok(());
+        }
+        _ => return Err(anyhow!("config subcommand required")),
+    };
+    let profile = profile_name(matches);
+    let resolved = config::load()?.resolve(profile);
+    let out = json!({
+        "profile": profile,
+        "inherits": &resolved.chain[1..],
+        "base_url": resolved.base_url,
+        "headers": config::redact_headers(&resolved.headers)
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    });
+    write_output(&out, true)
+}
 
-    let output = if matches.get_flag("raw") {
-        json!({
-            "status": response.status,
-            "headers": response.headers,
-            "body": response.body,
-        })
+fn handle_logout(matches: &clap::ArgMatches) -> Result<()> {
+    let profile = profile_name(matches);
+    if keychain::delete(profile)? {
+        write_stdout_line(&format!("Removed API key for profile {profile}"))
     } else {
-        response.body
-    };
+        write_stdout_line(&format!("No API key stored for profile {profile}"))
+    }
+}
 
-    write_output(&output, matches.get_flag("pretty"))?;
-    ensure_success(response.status, &output)?;
-    Ok(())
+fn read_api_key_stdin() -> Result<String> {
+    if std::io::stdin().is_terminal() {
+        eprint!("Plane API key: ");
+        std::io::stderr().flush().ok();
+    }
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("read api key from stdin")?;
+    let key = line.trim();
+    if key.is_empty() {
+        return Err(anyhow!("no API key provided"));
+    }
+    Ok(key.to_string())
 }
 
-fn find_op<'a>(tree: &'a CommandTree, res: &str, op: &str) -> Option<&'a Operation> {
-    tree.resources
-        .iter()
-        .find(|r| r.name == res)
-        .and_then(|r| r.ops.iter().find(|o| o.name == op))
+/// `--verbose` line naming the operation an abbreviated command resolved to.
+fn matched_op_note(resource: &str, op: &Operation) -> String {
+    let deprecated = if op.deprecated { " [deprecated]" } else { "" };
+    format!(
+        "matched {resource} {}: {} {}{deprecated}",
+        op.name, op.method, op.path
+    )
 }
 
 fn collect_path_params(op: &Operation, matches: &clap::ArgMatches) -> Result<HashMap<String, String>> {
     let mut params = HashMap::new();
     for param in &op.params {
-        let mut value = matches.get_one::<String>(&param.name).cloned();
-        if value.is_none() && is_workspace_param(&param.name) {
-            value = env::var("PLANE_WORKSPACE").ok();
-        }
+        let value = match matches.get_one::<String>(&param.name) {
+            Some(value) => Some(value.clone()),
+            None => param_fallback(param, workspace_flag(matches)).map(|(var, value)| {
+                verbose(matches, env_fallback_note(param, var, &value));
+                value
+            }),
+        };
         let value = value.ok_or_else(|| anyhow!("missing required argument --{}", param.flag))?;
         params.insert(param.name.clone(), value);
     }
     Ok(params)
 }
 
-fn is_workspace_param(name: &str) -> bool {
-    matches!(name, "slug" | "workspace" | "workspace_slug" | "workspaceSlug")
+/// The global `--workspace` slug, if given.
+fn workspace_flag(matches: &clap::ArgMatches) -> Option<&str> {
+    matches.get_one::<String>("workspace").map(String::as_str)
 }
 
-fn build_path(template: &str, params: &HashMap<String, String>) -> Result<String> {
-    let mut out = String::new();
-    let mut cursor = 0;
-    let bytes = template.as_bytes();
-
-    while let Some(start) = find_byte(bytes, b'<', cursor) {
-        let end = find_byte(bytes, b'>', start + 1).ok_or_else(|| anyhow!("invalid path template"))?;
-        out.push_str(&template[cursor..start]);
-        let token = &template[start + 1..end];
-        let name = token.split(':').nth(1).unwrap_or(token);
-        let value = params
-            .get(name)
-            .ok_or_else(|| anyhow!("missing value for {name}"))?;
-        out.push_str(value);
-        cursor = end + 1;
-    }
-
-    out.push_str(&template[cursor..]);
-    Ok(out)
+fn env_fallback_note(param: &Param, var: &str, value: &str) -> String {
+    format!("--{} not given; using {var}={value}", param.flag)
 }
 
-fn find_byte(haystack: &[u8], needle: u8, start: usize) -> Option<usize> {
-    haystack
-        .iter()
-        .enumerate()
-        .skip(start)
-        .find_map(|(idx, byte)| if *byte == needle { Some(idx) } else { None })
-}
-
-fn join_url(base: &str, base_path: &str, path: &str) -> String {
-    let base = base.trim_end_matches('/');
-    let base_path = base_path.trim_matches('/');
-    let path = path.trim_start_matches('/');
-
-    if base_path.is_empty() {
-        format!("{base}/{path}")
-    } else if path.is_empty() {
-        format!("{base}/{base_path}")
-    } else {
-        format!("{base}/{base_path}/{path}")
+/// Plane's `order_by=-created_at` convention: `-` prefix for descending.
+fn order_by(matches: &clap::ArgMatches) -> Option<String> {
+    let field = matches.get_one::<String>("order-by")?;
+    match matches.get_one::<String>("order").map(String::as_str) {
+        Some("desc") => Some(format!("-{}", field.trim_start_matches('-'))),
+        Some(_) => Some(field.trim_start_matches('-').to_string()),
+        None => Some(field.clone()),
     }
 }
 
-fn build_query_params(matches: &clap::ArgMatches) -> Result<Vec<(String, String)>> {
-    let mut params = Vec::new();
+/// `query_file` is the already-parsed `--query-file`, so the key check and `--explain`
+/// can reuse it instead of reading the file again.
+fn build_query_params(
+    matches: &clap::ArgMatches,
+    query_file: &[(String, String)],
+) -> Result<Vec<(String, String)>> {
+    let mut params = query_file.to_vec();
 
     if let Some(values) = matches.get_many::<String>("query") {
         for value in values {
@@ -424,13 +2266,28 @@ fn build_query_params(matches: &clap::ArgMatches) -> Result<Vec<(String, String)
         }
     }
 
-    if let Some(fields) = matches.get_one::<String>("fields") {
-        params.push(("fields".to_string(), fields.clone()));
+    let fields = requested_fields(matches)?;
+    if !fields.is_empty() {
+        params.push(("fields".to_string(), fields.join(",")));
     }
 
-    if let Some(expands) = matches.get_many::<String>("expand") {
-        for expand in expands {
-            params.push(("expand".to_string(), expand.clone()));
+    // Plane splits `expand` on commas; keys repeated across or within flags go once.
+    let mut seen = Vec::new();
+    for expand in expand_keys(matches) {
+        let fresh: Vec<&str> = expand
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .filter(|key| {
+                let new = !seen.contains(key);
+                if new {
+                    seen.push(*key);
+                }
+                new
+            })
+            .collect();
+        if !fresh.is_empty() {
+            params.push(("expand".to_string(), fresh.join(",")));
         }
     }
 
@@ -442,9 +2299,222 @@ fn build_query_params(matches: &clap::ArgMatches) -> Result<Vec<(String, String)
         params.push(("cursor".to_string(), cursor.clone()));
     }
 
+    if let Some(order_by) = order_by(matches) {
+        if params.iter().any(|(key, _)| key == "order_by") {
+            eprintln!("warning: --query order_by overrides --order-by/--order");
+        } else {
+            params.push(("order_by".to_string(), order_by));
+        }
+    }
+
+    Ok(params)
+}
+
+/// `--query-file`: one `key=value` per line; blank lines and `#` comments are skipped.
+fn read_query_file(matches: &clap::ArgMatches) -> Result<Vec<(String, String)>> {
+    let Some(path) = matches.get_one::<String>("query-file") else {
+        return Ok(Vec::new());
+    };
+    let raw = fs::read_to_string(path).with_context(|| format!("read --query-file {path}"))?;
+    parse_query_lines(&raw).with_context(|| format!("--query-file {path}"))
+}
+
+fn parse_query_lines(raw: &str) -> Result<Vec<(String, String)>> {
+    let mut params = Vec::new();
+    for (idx, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let pair = parse_query_pair(line).with_context(|| format!("line {}", idx + 1))?;
+        params.push(pair);
+    }
     Ok(params)
 }
 
+fn bool_query_params(op: &Operation, matches: &clap::ArgMatches) -> Vec<(String, String)> {
+    op.query
+        .iter()
+        .filter(|query| query.is_bool())
+        .filter_map(|query| {
+            let value = matches
+                .try_get_one::<String>(&bool_query_id(query))
+                .ok()
+                .flatten()?;
+            Some((query.name.clone(), value.clone()))
+        })
+        .collect()
+}
+
+/// Warns (or errors with `--strict-query`) on `--query`/`--query-file` keys the op
+/// does not declare. Ops without declared query params are not checked.
+fn check_query_keys(
+    op: &Operation,
+    matches: &clap::ArgMatches,
+    query_file: &[(String, String)],
+) -> Result<()> {
+    if op.query.is_empty() {
+        return Ok(());
+    }
+    let mut keys: Vec<String> = query_file.iter().map(|(key, _)| key.clone()).collect();
+    for value in matches.get_many::<String>("query").into_iter().flatten() {
+        keys.push(parse_query_pair(value)?.0);
+    }
+    let strict = matches.get_flag("strict-query");
+    for key in keys {
+        if op.query.iter().any(|param| param.name == key) {
+            continue;
+        }
+        let known: Vec<&str> = op.query.iter().map(|param| param.name.as_str()).collect();
+        let message = format!(
+            "unknown query param {key} for {} (known: {})",
+            op.name,
+            known.join(", ")
+        );
+        if strict {
+            return Err(anyhow!(message));
+        }
+        eprintln!("warning: {message}");
+    }
+    Ok(())
+}
+
+fn expand_keys(matches: &clap::ArgMatches) -> impl Iterator<Item = &str> {
+    matches
+        .get_many::<String>("expand")
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+}
+
+/// Warns (or errors with `--strict-query`) on `--expand` keys outside the op's `expand`
+/// list. Ops without a modeled list are not checked, so new expands still go through.
+fn check_expand_keys(op: &Operation, matches: &clap::ArgMatches) -> Result<()> {
+    if op.expand.is_empty() {
+        return Ok(());
+    }
+    let strict = matches.get_flag("strict-query");
+    let keys = expand_keys(matches).flat_map(|value| value.split(',').map(str::trim));
+    for key in keys.filter(|key| !key.is_empty()) {
+        if op.expand.iter().any(|known| known == key) {
+            continue;
+        }
+        let message = format!(
+            "unknown expand {key} for {} (known: {})",
+            op.name,
+            op.expand.join(", ")
+        );
+        if strict {
+            return Err(anyhow!(message));
+        }
+        eprintln!("warning: {message}");
+    }
+    Ok(())
+}
+
+/// Checks values of modeled integer/boolean query params: booleans are normalized
+/// (`yes`/`1`/`on` -> `true`), malformed values warn, or fail under `--strict-query`.
+/// Unmodeled keys pass through untouched.
+fn coerce_query(
+    op: &Operation,
+    query: Vec<(String, String)>,
+    strict: bool,
+) -> Result<Vec<(String, String)>> {
+    let mut out = Vec::with_capacity(query.len());
+    for (key, value) in query {
+        let Some(param) = op.query.iter().find(|param| param.name == key) else {
+            out.push((key, value));
+            continue;
+        };
+        match coerce_query_value(param, &value) {
+            Ok(value) => out.push((key, value)),
+            Err(expected) => {
+                let message = format!("query param {key}={value} is not {expected}");
+                if strict {
+                    return Err(anyhow!(message));
+                }
+                eprintln!("warning: {message}");
+                out.push((key, value));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn coerce_query_value(
+    param: &QueryParam,
+    value: &str,
+) -> std::result::Result<String, &'static str> {
+    match param.kind.as_deref() {
+        Some("bool" | "boolean") => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" | "on" => Ok("true".to_string()),
+            "false" | "no" | "0" | "off" => Ok("false".to_string()),
+            _ => Err("a boolean"),
+        },
+        Some("int" | "integer") => value
+            .trim()
+            .parse::<i64>()
+            .map(|number| number.to_string())
+            .map_err(|_| "an integer"),
+        Some("number") => value
+            .trim()
+            .parse::<f64>()
+            .map(|_| value.trim().to_string())
+            .map_err(|_| "a number"),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// All `--fields` values split on commas, in order, without duplicates.
+fn requested_fields(matches: &clap::ArgMatches) -> Result<Vec<String>> {
+    field_list(matches, "fields")
+}
+
+/// `--select` paths, projected locally from the response; nothing is sent to the server.
+fn selected_fields(matches: &clap::ArgMatches) -> Result<Vec<String>> {
+    field_list(matches, "select")
+}
+
+/// Comma-separated `--fields`/`--select` values; config.toml is only read when one
+/// of them names an `@preset`.
+fn field_list(matches: &clap::ArgMatches, id: &str) -> Result<Vec<String>> {
+    let parts: Vec<&str> = matches
+        .get_many::<String>(id)
+        .into_iter()
+        .flatten()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    let presets = if parts.iter().any(|part| part.starts_with('@')) {
+        config::load()?.fields
+    } else {
+        BTreeMap::new()
+    };
+    expand_fields(&parts, &presets)
+}
+
+/// Deduplicates in order and replaces each `@name` with its preset's fields. Preset
+/// contents are not checked; the server (or the local projection) decides what exists.
+fn expand_fields(parts: &[&str], presets: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut push = |field: &str| {
+        if !field.is_empty() && !fields.iter().any(|seen| seen == field) {
+            fields.push(field.to_string());
+        }
+    };
+    for part in parts {
+        let Some(name) = part.strip_prefix('@') else {
+            push(part);
+            continue;
+        };
+        let preset = presets.get(name).ok_or_else(|| {
+            anyhow!("unknown field preset @{name}; see `plane config fields list`")
+        })?;
+        preset.split(',').map(str::trim).for_each(&mut push);
+    }
+    Ok(fields)
+}
+
 fn parse_query_pair(input: &str) -> Result<(String, String)> {
     let mut parts = input.splitn(2, '=');
     let key = parts.next().unwrap_or_default().trim();
@@ -458,9 +2528,48 @@ fn parse_query_pair(input: &str) -> Result<(String, String)> {
 fn read_body(matches: &clap::ArgMatches) -> Result<Option<Value>> {
     let body_json = matches.get_one::<String>("body-json");
     let body_file = matches.get_one::<String>("body-file");
+    let base64_file = matches.get_one::<String>("body-base64-file");
+
+    let fields = matches.contains_id("field") || matches.contains_id("field-json");
+    let template = matches.get_one::<String>("body-template");
+
+    if matches.get_flag("no-body") {
+        let explicit = [body_json, body_file, base64_file, template]
+            .iter()
+            .any(|flag| flag.is_some());
+        if explicit || fields {
+            return Err(anyhow!("--no-body cannot be combined with a body flag"));
+        }
+        return Ok(None);
+    }
+
+    let given = [body_json, body_file, base64_file, template]
+        .iter()
+        .filter(|flag| flag.is_some())
+        .count()
+        + usize::from(fields);
+    if given > 1 {
+        return Err(anyhow!(
+            "use only one of --body-json, --body-file, --body-base64-file, --body-template or --field"
+        ));
+    }
+
+    if let Some(path) = template {
+        let source = fs::read_to_string(path).with_context(|| format!("read {path}"))?;
+        return render_body_template(&source, &template_vars(matches)?).map(Some);
+    }
+
+    if fields {
+        return field_body(matches).map(Some);
+    }
 
-    if body_json.is_some() && body_file.is_some() {
-        return Err(anyhow!("use only one of --body-json or --body-file"));
+    if let Some(path) = base64_file {
+        let bytes = fs::read(path).with_context(|| format!("read {path}"))?;
+        let encoded = Value::String(base64_body(&bytes));
+        return Ok(Some(match matches.get_one::<String>("body-base64-field") {
+            Some(field) => serde_json::json!({ field.as_str(): encoded }),
+            None => encoded,
+        }));
     }
 
     if let Some(raw) = body_json {
@@ -477,40 +2586,292 @@ fn read_body(matches: &clap::ArgMatches) -> Result<Option<Value>> {
     Ok(None)
 }
 
+/// Object body from `--field key=value` (typed scalars) then `--field-json key=<json>`.
+fn field_body(matches: &clap::ArgMatches) -> Result<Value> {
+    let mut body = Value::Object(Map::new());
+    for raw in matches.get_many::<String>("field").into_iter().flatten() {
+        let (key, value) = split_field(raw, "--field")?;
+        set_field(&mut body, key, coerce_field(value))?;
+    }
+    for raw in matches
+        .get_many::<String>("field-json")
+        .into_iter()
+        .flatten()
+    {
+        let (key, value) = split_field(raw, "--field-json")?;
+        let value = serde_json::from_str(value).with_context(|| format!("--field-json {key}"))?;
+        set_field(&mut body, key, value)?;
+    }
+    Ok(body)
+}
+
+fn split_field<'a>(raw: &'a str, flag: &str) -> Result<(&'a str, &'a str)> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim(), value)),
+        _ => Err(anyhow!("invalid {flag} {raw}; expected KEY=VALUE")),
+    }
+}
+
+fn coerce_field(value: &str) -> Value {
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => value
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| value.parse::<f64>().map(Value::from))
+            .ok()
+            .filter(|number| !number.is_null())
+            .unwrap_or_else(|| Value::String(value.to_string())),
+    }
+}
+
+/// `assignee.id` sets `{"assignee": {"id": ...}}`, creating objects along the way.
+fn set_field(body: &mut Value, key: &str, value: Value) -> Result<()> {
+    let mut target = body;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let object = target
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("field {key}: {part} is nested under a non-object value"))?;
+        if parts.peek().is_none() {
+            object.insert(part.to_string(), value);
+            return Ok(());
+        }
+        target = object
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    Ok(())
+}
+
+fn template_vars(matches: &clap::ArgMatches) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for raw in matches.get_many::<String>("var").into_iter().flatten() {
+        let (key, value) = split_field(raw, "--var")?;
+        vars.insert(key.to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
+/// Renders a minijinja body template; undefined variables are errors unless the
+/// template gives a default (`{{ title | default("Untitled") }}`).
+fn render_body_template(source: &str, vars: &BTreeMap<String, String>) -> Result<Value> {
+    let mut env = minijinja::Environment::new();
+    env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+    let rendered = env
+        .render_str(source, vars)
+        .map_err(|err| anyhow!("render --body-template: {err:#}"))?;
+    serde_json::from_str(&rendered)
+        .with_context(|| format!("--body-template did not render valid JSON:\n{rendered}"))
+}
+
+fn base64_body(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
 fn write_output(value: &Value, pretty: bool) -> Result<()> {
+    write_stdout_line(&render_json(value, pretty)?)
+}
+
+fn render_json(value: &Value, pretty: bool) -> Result<String> {
     if pretty {
-        write_stdout_line(&serde_json::to_string_pretty(value)?)?;
+        Ok(serde_json::to_string_pretty(value)?)
     } else {
-        write_stdout_line(&serde_json::to_string(value)?)?;
+        Ok(serde_json::to_string(value)?)
     }
-    Ok(())
 }
 
 fn write_stdout_line(value: &str) -> Result<()> {
+    write_stdout(value, true)
+}
+
+fn write_stdout(value: &str, newline: bool) -> Result<()> {
     let mut out = std::io::stdout().lock();
-    if let Err(err) = out.write_all(value.as_bytes()) {
+    let mut result = out.write_all(value.as_bytes());
+    if result.is_ok() && newline {
+        result = out.write_all(b"\n");
+    }
+    if result.is_ok() {
+        result = out.flush();
+    }
+    if let Err(err) = result {
         if err.kind() == std::io::ErrorKind::BrokenPipe {
             std::process::exit(0);
         }
         return Err(err.into());
     }
-    if let Err(err) = out.write_all(b"\n") {
-        if err.kind() == std::io::ErrorKind::BrokenPipe {
-            std::process::exit(0);
+    Ok(())
+}
+
+/// Loads `--env-file`, or `./.env` when `PLANE_DOTENV=1`; real env vars win.
+fn load_dotenv(matches: &clap::ArgMatches) -> Result<()> {
+    if let Some(path) = matches.get_one::<String>("env-file") {
+        dotenvy::from_path(path).with_context(|| format!("load env file {path}"))?;
+        return Ok(());
+    }
+    if env::var("PLANE_DOTENV").is_ok_and(|value| value == "1") {
+        match dotenvy::dotenv() {
+            Ok(_) => {}
+            Err(err) if err.not_found() => {}
+            Err(err) => return Err(err).context("load .env"),
         }
-        return Err(err.into());
     }
     Ok(())
 }
 
-fn resolve_api_base(tree: &CommandTree) -> Result<(String, String)> {
-    if let Ok(base_url) = env::var("PLANE_BASE_URL") {
-        return split_base_url(&base_url, &tree.base_path);
+fn resolve_api_key(matches: &clap::ArgMatches) -> Result<String> {
+    if let Some(key) = explicit_api_key(matches)? {
+        return Ok(key);
+    }
+    if let Ok(key) = env::var("PLANE_API_KEY") {
+        return Ok(key);
+    }
+    let config = config::load()?;
+    for profile in config.chain(profile_name(matches)) {
+        match keychain::load(profile) {
+            Ok(Some(key)) => return Ok(key),
+            Ok(None) => {}
+            Err(err) if keychain::is_unavailable(&err) => {
+                verbose(
+                    matches,
+                    format_args!("keyring unavailable, skipping: {err:#}"),
+                );
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Err(anyhow!("PLANE_API_KEY missing"))
+}
+
+fn explicit_api_key(matches: &clap::ArgMatches) -> Result<Option<String>> {
+    if let Some(key) = matches.get_one::<String>("api-key") {
+        return Ok(Some(key.clone()));
+    }
+    if let Some(path) = matches.get_one::<String>("api-key-file") {
+        let raw = fs::read_to_string(path).with_context(|| format!("read api key file {path}"))?;
+        let key = raw.trim();
+        if key.is_empty() {
+            return Err(anyhow!("api key file {path} is empty"));
+        }
+        return Ok(Some(key.to_string()));
+    }
+    if let Some(command) = matches.get_one::<String>("api-key-command") {
+        return run_api_key_command(command).map(Some);
+    }
+    Ok(None)
+}
+
+fn profile_name(matches: &clap::ArgMatches) -> &str {
+    matches
+        .get_one::<String>("profile")
+        .map(String::as_str)
+        .unwrap_or("default")
+}
+
+/// Runs `command` through the platform shell: `sh -c`, or `cmd /C` on Windows.
+fn run_api_key_command(command: &str) -> Result<String> {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+    let output = std::process::Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .with_context(|| format!("run api key command `{command}`"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "api key command `{command}` failed ({}): {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    let stdout = String::from_utf8(output.stdout).context("api key command output is not utf-8")?;
+    let key = stdout.trim();
+    if key.is_empty() {
+        return Err(anyhow!("api key command `{command}` printed nothing"));
     }
+    Ok(key.to_string())
+}
 
-    let api_url = env::var("PLANE_API_URL").unwrap_or_else(|_| "https://api.plane.so".to_string());
-    let base_path = env::var("PLANE_API_BASE_PATH").unwrap_or_else(|_| tree.base_path.clone());
-    Ok((api_url, base_path))
+/// Precedence: `--api-url`/`--base-path` > `PLANE_BASE_URL` > `PLANE_API_URL`/`PLANE_API_BASE_PATH`
+/// > profile `base_url` > tree default.
+fn resolve_api_base(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<(String, String)> {
+    let profile = config::load()?.resolve(profile_name(matches));
+    let mut base =
+        resolve_api_base_env(tree, profile.base_url.as_deref(), |var| env::var(var).ok())?;
+    if let Some(flag) = matches.get_one::<String>("api-url") {
+        base.api_url = flag.clone();
+        base.api_url_from = "--api-url";
+    }
+    if let Some(flag) = matches.get_one::<String>("base-path") {
+        base.base_path = flag.clone();
+        base.base_path_from = "--base-path";
+    }
+    verbose(matches, &base);
+    Ok((base.api_url, base.base_path))
+}
+
+/// Resolved host and base path, with the flag, env var, or default each came from.
+struct ApiBase {
+    api_url: String,
+    base_path: String,
+    api_url_from: &'static str,
+    base_path_from: &'static str,
+}
+
+impl std::fmt::Display for ApiBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "api_url {} (from {}), base_path {} (from {})",
+            self.api_url, self.api_url_from, self.base_path, self.base_path_from
+        )
+    }
+}
+
+/// `PLANE_BASE_URL` > `PLANE_API_URL`/`PLANE_API_BASE_PATH` > profile `base_url` > tree default,
+/// each part resolved on its own; `var` looks up the environment.
+fn resolve_api_base_env(
+    tree: &CommandTree,
+    profile_base_url: Option<&str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<ApiBase> {
+    let split = |base_url: &str, from: &'static str| {
+        split_base_url(base_url, &tree.base_path).map(|(api_url, base_path)| ApiBase {
+            api_url,
+            base_path,
+            api_url_from: from,
+            base_path_from: from,
+        })
+    };
+    if let Some(base_url) = var("PLANE_BASE_URL") {
+        return split(&base_url, "PLANE_BASE_URL");
+    }
+    let mut base = match profile_base_url {
+        Some(base_url) => split(base_url, "profile base_url").context("profile base_url")?,
+        None => ApiBase {
+            api_url: "https://api.plane.so".to_string(),
+            base_path: tree.base_path.clone(),
+            api_url_from: "default",
+            base_path_from: "command tree default",
+        },
+    };
+    if let Some(api_url) = var("PLANE_API_URL") {
+        base.api_url = api_url;
+        base.api_url_from = "PLANE_API_URL";
+    }
+    if let Some(base_path) = var("PLANE_API_BASE_PATH") {
+        base.base_path = base_path;
+        base.base_path_from = "PLANE_API_BASE_PATH";
+    }
+    Ok(base)
 }
 
 fn split_base_url(base_url: &str, default_path: &str) -> Result<(String, String)> {
@@ -0,0 +1,130 @@
+use crate::command_tree::{CommandTree, Operation};
+use serde::Serialize;
+
+/// Bumped only on breaking changes (renamed/removed fields); new fields may be added
+/// within a version.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Stable operation catalog emitted by `plane manifest`, independent of the
+/// `CommandTree` serde shape.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub manifest_version: u32,
+    pub tree_version: u32,
+    pub base_path: String,
+    pub operations: Vec<ManifestOp>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestOp {
+    pub resource: String,
+    pub op: String,
+    pub method: String,
+    pub path: String,
+    pub deprecated: bool,
+    pub params: Vec<ManifestParam>,
+}
+
+/// `location` is `path` or `query`; `flag` is null for query params only settable
+/// through `--query KEY=VALUE`.
+#[derive(Debug, Serialize)]
+pub struct ManifestParam {
+    pub name: String,
+    pub flag: Option<String>,
+    pub location: &'static str,
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Single-operation contract emitted by `plane schema`. `body` is null until request
+/// bodies are modeled in the command tree; it is reserved so adding it is not breaking.
+#[derive(Debug, Serialize)]
+pub struct OpSchema {
+    pub manifest_version: u32,
+    pub resource: String,
+    pub op: String,
+    pub method: String,
+    pub path: String,
+    pub example_url: String,
+    pub path_params: Vec<ManifestParam>,
+    pub query_params: Vec<ManifestParam>,
+    pub body: Option<serde_json::Value>,
+}
+
+pub fn schema(resource: &str, op: &Operation, example_url: String) -> OpSchema {
+    let (path_params, query_params) = params(op)
+        .into_iter()
+        .partition(|param| param.location == "path");
+    OpSchema {
+        manifest_version: MANIFEST_VERSION,
+        resource: resource.to_string(),
+        op: op.name.clone(),
+        method: op.method.clone(),
+        path: op.path.clone(),
+        example_url,
+        path_params,
+        query_params,
+        body: None,
+    }
+}
+
+pub fn build(tree: &CommandTree) -> Manifest {
+    let operations = tree
+        .resources
+        .iter()
+        .flat_map(|res| {
+            res.ops.iter().map(|op| ManifestOp {
+                resource: res.name.clone(),
+                op: op.name.clone(),
+                method: op.method.clone(),
+                path: op.path.clone(),
+                deprecated: op.deprecated,
+                params: params(op),
+            })
+        })
+        .collect();
+    Manifest {
+        manifest_version: MANIFEST_VERSION,
+        tree_version: tree.version,
+        base_path: tree.base_path.clone(),
+        operations,
+    }
+}
+
+fn params(op: &Operation) -> Vec<ManifestParam> {
+    let path = op.params.iter().map(|param| ManifestParam {
+        name: param.name.clone(),
+        flag: Some(param.flag.clone()),
+        location: "path",
+        required: true,
+        kind: path_param_type(&op.path, &param.name).to_string(),
+    });
+    let query = op.query.iter().map(|query| ManifestParam {
+        name: query.name.clone(),
+        flag: query.is_bool().then(|| query.flag()),
+        location: "query",
+        required: false,
+        kind: match query.kind.as_deref() {
+            Some("bool" | "boolean") => "boolean",
+            Some("int" | "integer") => "integer",
+            _ => "string",
+        }
+        .to_string(),
+    });
+    path.chain(query).collect()
+}
+
+/// Type from the `<uuid:project_id>` path token; untyped and `:name` tokens are strings.
+fn path_param_type(template: &str, name: &str) -> &'static str {
+    let converter = crate::command_tree::path_tokens(template)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|token| token.name == name)
+        .and_then(|token| token.converter);
+    match converter {
+        Some("uuid") => "uuid",
+        Some("int") => "integer",
+        _ => "string",
+    }
+}
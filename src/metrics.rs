@@ -0,0 +1,101 @@
+use crate::http::Counters;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+// Process-wide so `--batch` and `export` workers, each with their own client, add up.
+static COUNTERS: LazyLock<Arc<Counters>> = LazyLock::new(Arc::default);
+
+/// Attached to every client `build_client` makes.
+pub fn counters() -> Arc<Counters> {
+    Arc::clone(&COUNTERS)
+}
+
+/// Counters for one run, as written to `--metrics-file`.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub requests: u64,
+    pub retries: u64,
+    pub bytes: u64,
+    pub statuses: BTreeMap<u16, u64>,
+    pub duration: Duration,
+    pub success: bool,
+}
+
+pub fn snapshot(duration: Duration, success: bool) -> Snapshot {
+    Snapshot {
+        requests: COUNTERS.requests(),
+        retries: COUNTERS.retries(),
+        bytes: COUNTERS.bytes(),
+        statuses: COUNTERS.statuses(),
+        duration,
+        success,
+    }
+}
+
+/// Prometheus text exposition format, for node_exporter's textfile collector.
+pub fn render(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{name}{labels} {value}");
+        }
+    };
+    let plain = |value: String| vec![(String::new(), value)];
+    metric(
+        "plane_requests_total",
+        "counter",
+        "HTTP requests sent, including retries.",
+        &plain(snapshot.requests.to_string()),
+    );
+    metric(
+        "plane_retries_total",
+        "counter",
+        "Requests retried after a retryable status or connection error.",
+        &plain(snapshot.retries.to_string()),
+    );
+    metric(
+        "plane_response_bytes_total",
+        "counter",
+        "Response body bytes received.",
+        &plain(snapshot.bytes.to_string()),
+    );
+    let statuses: Vec<(String, String)> = snapshot
+        .statuses
+        .iter()
+        .map(|(status, count)| (format!("{{status=\"{status}\"}}"), count.to_string()))
+        .collect();
+    metric(
+        "plane_responses_total",
+        "counter",
+        "Responses received, by HTTP status.",
+        &statuses,
+    );
+    metric(
+        "plane_run_duration_seconds",
+        "gauge",
+        "Wall-clock duration of the run.",
+        &plain(format!("{:.3}", snapshot.duration.as_secs_f64())),
+    );
+    metric(
+        "plane_run_success",
+        "gauge",
+        "1 if the command exited successfully, else 0.",
+        &plain(u8::from(snapshot.success).to_string()),
+    );
+    out
+}
+
+/// `--metrics-file`: best-effort, so a failed write only warns. Written via a temp
+/// file and rename so a collector never reads a partial file.
+pub fn write(path: &str, snapshot: &Snapshot) {
+    let tmp = format!("{path}.tmp");
+    let written = fs::write(&tmp, render(snapshot)).and_then(|()| fs::rename(&tmp, path));
+    if let Err(err) = written {
+        eprintln!("warning: cannot write --metrics-file {path}: {err}");
+    }
+}
@@ -0,0 +1,425 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value};
+
+/// A display column: dotted `path` into each row plus its header label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub path: String,
+    pub header: String,
+}
+
+/// Parses `id:ID,name:Name,state.name` (header defaults to the path).
+pub fn parse_columns(spec: &str) -> Result<Vec<Column>> {
+    let mut columns = Vec::new();
+    for part in spec
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let (path, header) = match part.split_once(':') {
+            Some((path, header)) => (path.trim(), header.trim()),
+            None => (part, part),
+        };
+        if path.is_empty() {
+            return Err(anyhow!("invalid column spec: {part}"));
+        }
+        columns.push(Column {
+            path: path.to_string(),
+            header: if header.is_empty() { path } else { header }.to_string(),
+        });
+    }
+    if columns.is_empty() {
+        return Err(anyhow!("no columns in spec: {spec}"));
+    }
+    Ok(columns)
+}
+
+/// Looks up `a.b.0.c` in nested objects/arrays.
+pub fn resolve_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |current, key| match current {
+            Value::Object(map) => map.get(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|idx| items.get(idx)),
+            _ => None,
+        })
+}
+
+/// Rows for tabular output: array bodies, Plane `results` envelopes, or a single object.
+pub fn rows(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => match map.get("results") {
+            Some(Value::Array(items)) => items.iter().collect(),
+            _ => vec![value],
+        },
+        other => vec![other],
+    }
+}
+
+/// `--select`: keeps only `fields` (dotted paths) of each row; arrays and `results`
+/// envelopes are projected per item, leaving the envelope's other keys intact.
+pub fn select(value: &Value, fields: &[String]) -> Value {
+    match value {
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| select_row(item, fields)).collect())
+        }
+        Value::Object(map) if map.get("results").is_some_and(Value::is_array) => {
+            let mut envelope = map.clone();
+            envelope.insert("results".to_string(), select(&map["results"], fields));
+            Value::Object(envelope)
+        }
+        other => select_row(other, fields),
+    }
+}
+
+fn select_row(row: &Value, fields: &[String]) -> Value {
+    if !row.is_object() {
+        return row.clone();
+    }
+    let mut out = Map::new();
+    for field in fields {
+        if let Some(found) = resolve_path(row, field) {
+            insert_path(&mut out, field, found.clone());
+        }
+    }
+    Value::Object(out)
+}
+
+fn insert_path(out: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        None => {
+            out.insert(path.to_string(), value);
+        }
+        Some((head, rest)) => {
+            let child = out
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(child) = child {
+                insert_path(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Rows only for list-shaped bodies (arrays or `results` envelopes).
+pub fn list_rows(value: &Value) -> Option<Vec<&Value>> {
+    match value {
+        Value::Array(items) => Some(items.iter().collect()),
+        Value::Object(map) => match map.get("results") {
+            Some(Value::Array(items)) => Some(items.iter().collect()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Columns from the first row's keys when none were requested.
+pub fn default_columns(rows: &[&Value]) -> Vec<Column> {
+    match rows.first() {
+        Some(Value::Object(map)) => map
+            .keys()
+            .map(|key| Column {
+                path: key.clone(),
+                header: key.clone(),
+            })
+            .collect(),
+        _ => vec![Column {
+            path: String::new(),
+            header: "value".to_string(),
+        }],
+    }
+}
+
+/// `--require-results`: `null`, `[]`, or an envelope with empty `results`.
+pub fn is_empty_result(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map
+            .get("results")
+            .and_then(Value::as_array)
+            .is_some_and(Vec::is_empty),
+        _ => false,
+    }
+}
+
+/// `--raw-scalar`: scalars as shell-friendly text; `None` for objects and arrays.
+pub fn raw_scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some(String::new()),
+        Value::String(text) => Some(text.clone()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// `--flatten`: one dotted column per leaf (`state.name`, `labels.0.name`) across all
+/// rows, in first-seen order. Values nested deeper than `depth` stay one JSON cell.
+pub fn flat_columns(rows: &[&Value], depth: usize) -> Vec<Column> {
+    let mut paths = Vec::new();
+    for row in rows {
+        collect_leaves(row, String::new(), depth, &mut paths);
+    }
+    if paths.is_empty() {
+        return default_columns(rows);
+    }
+    paths
+        .into_iter()
+        .map(|path| Column {
+            header: path.clone(),
+            path,
+        })
+        .collect()
+}
+
+fn collect_leaves(value: &Value, prefix: String, depth: usize, out: &mut Vec<String>) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) if depth > 0 && !map.is_empty() => map
+            .iter()
+            .map(|(key, child)| (key.clone(), child))
+            .collect(),
+        Value::Array(items) if depth > 0 && !items.is_empty() && !prefix.is_empty() => items
+            .iter()
+            .enumerate()
+            .map(|(idx, child)| (idx.to_string(), child))
+            .collect(),
+        // Empty nested containers add no column; other rows' leaves cover them.
+        Value::Object(map) if map.is_empty() && !prefix.is_empty() => return,
+        Value::Array(items) if items.is_empty() && !prefix.is_empty() => return,
+        _ => {
+            if !prefix.is_empty() && !out.contains(&prefix) {
+                out.push(prefix);
+            }
+            return;
+        }
+    };
+    for (key, child) in children {
+        let path = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        collect_leaves(child, path, depth - 1, out);
+    }
+}
+
+/// Scalars as plain text, null/missing as empty, nested values as compact JSON.
+pub fn cell(row: &Value, path: &str) -> String {
+    let value = if path.is_empty() {
+        Some(row)
+    } else {
+        resolve_path(row, path)
+    };
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+pub fn render_table(value: &Value, columns: Option<&[Column]>) -> String {
+    let rows = rows(value);
+    let defaults;
+    let columns = match columns {
+        Some(columns) => columns,
+        None => {
+            defaults = default_columns(&rows);
+            &defaults
+        }
+    };
+
+    let mut grid: Vec<Vec<String>> = Vec::with_capacity(rows.len() + 1);
+    grid.push(columns.iter().map(|col| col.header.clone()).collect());
+    for row in &rows {
+        grid.push(columns.iter().map(|col| cell(row, &col.path)).collect());
+    }
+
+    let mut widths = vec![0; columns.len()];
+    for line in &grid {
+        for (idx, text) in line.iter().enumerate() {
+            widths[idx] = widths[idx].max(text.chars().count());
+        }
+    }
+
+    let mut out = Vec::with_capacity(grid.len());
+    for line in &grid {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(text, width)| format!("{text:<width$}"))
+            .collect();
+        out.push(cells.join("  ").trim_end().to_string());
+    }
+    out.join("\n")
+}
+
+/// CSV (`,`) or TSV (`\t`) with a header row; list-shaped bodies only.
+pub fn render_delimited(
+    value: &Value,
+    columns: Option<&[Column]>,
+    delimiter: u8,
+) -> Result<String> {
+    let rows = list_rows(value).ok_or_else(|| {
+        anyhow!("csv/tsv output needs an array or `results` body; use --output json")
+    })?;
+    let defaults;
+    let columns = match columns {
+        Some(columns) => columns,
+        None => {
+            defaults = default_columns(&rows);
+            &defaults
+        }
+    };
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer.write_record(columns.iter().map(|col| col.header.as_str()))?;
+    for row in &rows {
+        writer.write_record(columns.iter().map(|col| cell(row, &col.path)))?;
+    }
+    let bytes = writer.into_inner().map_err(|err| anyhow!("{err}"))?;
+    let text = String::from_utf8(bytes)?;
+    Ok(text.trim_end_matches('\n').to_string())
+}
+
+/// Renders `template` (Handlebars) against the body. `{{#each (rows this)}}` iterates
+/// arrays and `results` envelopes alike; output is not HTML-escaped.
+pub fn render_template(template: &str, value: &Value) -> Result<String> {
+    let mut hb = handlebars::Handlebars::new();
+    hb.register_escape_fn(handlebars::no_escape);
+    hb.register_helper("rows", Box::new(rows_helper));
+    hb.register_helper("json", Box::new(json_helper));
+    hb.register_template_string("template", template)
+        .map_err(|err| anyhow!("{err}"))?;
+    hb.render("template", value)
+        .map_err(|err| anyhow!("template render error: {err}"))
+}
+
+handlebars::handlebars_helper!(rows_helper: |value: Json| {
+    Value::Array(rows(value).into_iter().cloned().collect())
+});
+
+handlebars::handlebars_helper!(json_helper: |value: Json| value.to_string());
+
+const KEY: &str = "\x1b[34m";
+const STRING: &str = "\x1b[32m";
+const NUMBER: &str = "\x1b[36m";
+const BOOL: &str = "\x1b[33m";
+const NULL: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Same layout as `serde_json::to_string_pretty`, with ANSI colors for tokens.
+pub fn colorize_pretty(value: &Value) -> String {
+    let mut out = String::new();
+    write_colored(&mut out, value, 0);
+    out
+}
+
+fn write_colored(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Null => paint(out, NULL, "null"),
+        Value::Bool(flag) => paint(out, BOOL, &flag.to_string()),
+        Value::Number(number) => paint(out, NUMBER, &number.to_string()),
+        Value::String(_) => paint(out, STRING, &value.to_string()),
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Object(map) if map.is_empty() => out.push_str("{}"),
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for (idx, item) in items.iter().enumerate() {
+                indent(out, depth + 1);
+                write_colored(out, item, depth + 1);
+                out.push_str(if idx + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            indent(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push_str("{\n");
+            for (idx, (key, item)) in map.iter().enumerate() {
+                indent(out, depth + 1);
+                paint(out, KEY, &Value::String(key.clone()).to_string());
+                out.push_str(": ");
+                write_colored(out, item, depth + 1);
+                out.push_str(if idx + 1 < map.len() { ",\n" } else { "\n" });
+            }
+            indent(out, depth);
+            out.push('}');
+        }
+    }
+}
+
+fn paint(out: &mut String, color: &str, text: &str) {
+    out.push_str(color);
+    out.push_str(text);
+    out.push_str(RESET);
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+/// Line width under which `--compact` keeps an array or object on one line.
+pub const COMPACT_WIDTH: usize = 80;
+
+/// Pretty layout, except containers that fit in `width` (after indent and key) stay
+/// on one line: `{"id": 1, "name": "A"}`.
+pub fn render_compact(value: &Value, width: usize) -> String {
+    let mut out = String::new();
+    write_compact(&mut out, value, 0, 0, width);
+    out
+}
+
+fn write_compact(out: &mut String, value: &Value, depth: usize, prefix: usize, width: usize) {
+    let inline = one_line(value);
+    if depth * 2 + prefix + inline.len() <= width {
+        out.push_str(&inline);
+        return;
+    }
+    match value {
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for (idx, item) in items.iter().enumerate() {
+                indent(out, depth + 1);
+                write_compact(out, item, depth + 1, 0, width);
+                out.push_str(if idx + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            indent(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push_str("{\n");
+            for (idx, (key, item)) in map.iter().enumerate() {
+                indent(out, depth + 1);
+                let key = Value::String(key.clone()).to_string();
+                out.push_str(&key);
+                out.push_str(": ");
+                write_compact(out, item, depth + 1, key.len() + 2, width);
+                out.push_str(if idx + 1 < map.len() { ",\n" } else { "\n" });
+            }
+            indent(out, depth);
+            out.push('}');
+        }
+        _ => out.push_str(&inline),
+    }
+}
+
+fn one_line(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(one_line).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        Value::Object(map) => {
+            let fields: Vec<String> = map
+                .iter()
+                .map(|(key, item)| format!("{}: {}", Value::String(key.clone()), one_line(item)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        _ => value.to_string(),
+    }
+}
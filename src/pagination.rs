@@ -0,0 +1,409 @@
+use crate::http::{HttpClient, ResponseData};
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Map, Value, json};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Which cursor pages to collect: skip `skip` pages, then take `take` (all if `None`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    pub skip: usize,
+    pub take: Option<usize>,
+}
+
+impl PageRange {
+    pub fn all() -> Self {
+        Self {
+            skip: 0,
+            take: None,
+        }
+    }
+
+    /// Parses `START:END` (1-based, inclusive).
+    pub fn parse(input: &str) -> Result<Self> {
+        let (start, end) = input
+            .split_once(':')
+            .ok_or_else(|| anyhow!("invalid --pages {input}; expected START:END"))?;
+        let start: usize = start
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("invalid --pages start: {start}"))?;
+        let end: usize = end
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("invalid --pages end: {end}"))?;
+        if start == 0 || end < start {
+            return Err(anyhow!("invalid --pages {input}; need 1 <= START <= END"));
+        }
+        Ok(Self {
+            skip: start - 1,
+            take: Some(end - start + 1),
+        })
+    }
+}
+
+/// Reported after every collected page.
+#[derive(Debug, Clone, Copy)]
+pub struct PageProgress {
+    pub pages: usize,
+    pub items: usize,
+    pub total_count: Option<u64>,
+}
+
+/// What to send for every page of a paginated request.
+#[derive(Debug, Clone)]
+pub struct PageRequest<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub query: &'a [(String, String)],
+    pub body: Option<Value>,
+    /// Checked between pages; once set, the walk stops and keeps what it has (the
+    /// CLI sets it on Ctrl-C).
+    pub stop: Option<&'a AtomicBool>,
+}
+
+/// Where to resume: the next page's position and how many pages came before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resume {
+    pub position: Position,
+    pub page: usize,
+}
+
+/// `next_cursor` for cursor pagination, `offset` for older offset envelopes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Position {
+    Cursor(String),
+    Offset(u64),
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cursor(cursor) => write!(f, "cursor {cursor}"),
+            Self::Offset(offset) => write!(f, "offset {offset}"),
+        }
+    }
+}
+
+/// `--cursor-file`: saved after every page, removed once pagination completes.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    path: PathBuf,
+}
+
+impl Checkpoint {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Reads `{"next_cursor": ..., "page": N}` or `{"next_offset": N, "page": N}`; a bare
+    /// cursor string resumes at page 0.
+    pub fn load(&self) -> Result<Option<Resume>> {
+        let raw = match fs::read_to_string(&self.path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).with_context(|| format!("read {}", self.path.display())),
+        };
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        let Ok(saved) = serde_json::from_str::<Value>(raw) else {
+            return Ok(Some(Resume {
+                position: Position::Cursor(raw.to_string()),
+                page: 0,
+            }));
+        };
+        let position = match (
+            saved.get("next_cursor").and_then(Value::as_str),
+            saved.get("next_offset").and_then(Value::as_u64),
+        ) {
+            (Some(cursor), _) => Position::Cursor(cursor.to_string()),
+            (None, Some(offset)) => Position::Offset(offset),
+            (None, None) => {
+                return Err(anyhow!(
+                    "{}: missing next_cursor or next_offset",
+                    self.path.display()
+                ));
+            }
+        };
+        Ok(Some(Resume {
+            position,
+            page: saved.get("page").and_then(Value::as_u64).unwrap_or(0) as usize,
+        }))
+    }
+
+    pub fn save(&self, resume: &Resume) -> Result<()> {
+        let text = match &resume.position {
+            Position::Cursor(cursor) => json!({"next_cursor": cursor, "page": resume.page}),
+            Position::Offset(offset) => json!({"next_offset": offset, "page": resume.page}),
+        }
+        .to_string();
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, text).with_context(|| format!("write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path).with_context(|| format!("write {}", self.path.display()))
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("remove {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Follows Plane's `next_cursor` (or older `offset`) pagination and combines `results` into one array.
+/// Non-paginated bodies and error responses are returned as-is.
+pub fn fetch_pages(
+    client: &HttpClient,
+    request: PageRequest,
+    range: PageRange,
+    checkpoint: Option<&Checkpoint>,
+    on_page: &mut dyn FnMut(PageProgress),
+) -> Result<ResponseData> {
+    let mut results = Vec::new();
+    let mut last = None;
+    let mut taken = 0;
+    let mut visit = |_page: usize, response: ResponseData| -> Result<()> {
+        let total_count = total_count(&response.body);
+        if taken == 0
+            && let Some(total) = total_count
+        {
+            // Capped so a bogus count can't trigger a huge allocation.
+            results.reserve(total.min(PRESIZE_LIMIT) as usize);
+        }
+        let items = response.body.get("results").and_then(Value::as_array);
+        results.extend(items.into_iter().flatten().cloned());
+        taken += 1;
+        on_page(PageProgress {
+            pages: taken,
+            items: results.len(),
+            total_count,
+        });
+        last = Some((response.status, response.headers));
+        Ok(())
+    };
+    if let Some(response) = walk_pages(client, request, range, checkpoint, &mut visit)? {
+        return Ok(response);
+    }
+    let (status, headers) = last.ok_or_else(|| anyhow!("no pages in range"))?;
+    Ok(ResponseData {
+        status,
+        headers,
+        body: Value::Array(results),
+    })
+}
+
+/// Calls `visit(page_number, response)` (1-based) for each page in `range` without
+/// keeping earlier pages around. Returns `Some(response)` when the first response is
+/// not a paginated list, or when any page fails, so the caller can report it as-is.
+pub fn walk_pages(
+    client: &HttpClient,
+    request: PageRequest,
+    range: PageRange,
+    checkpoint: Option<&Checkpoint>,
+    visit: &mut dyn FnMut(usize, ResponseData) -> Result<()>,
+) -> Result<Option<ResponseData>> {
+    let base_query: Vec<(String, String)> = request
+        .query
+        .iter()
+        .filter(|(key, _)| key != "cursor" && key != "offset")
+        .cloned()
+        .collect();
+    let per_page = query_number(request.query, "per_page").or(query_number(request.query, "limit"));
+    let mut offset = query_number(request.query, "offset");
+    let mut cursor: Option<String> = None;
+    let mut page = 0;
+    let mut taken = 0;
+    if let Some(resume) = checkpoint.map(Checkpoint::load).transpose()?.flatten() {
+        eprintln!("resuming after page {} ({})", resume.page, resume.position);
+        match resume.position {
+            Position::Cursor(next) => cursor = Some(next),
+            Position::Offset(next) => offset = Some(next),
+        }
+        page = resume.page;
+    }
+
+    let mut fetched = false;
+    loop {
+        // A stop request lands between pages; the checkpoint keeps the position.
+        if fetched && request.stop.is_some_and(|stop| stop.load(Ordering::SeqCst)) {
+            return Ok(None);
+        }
+        fetched = true;
+        let mut query = base_query.clone();
+        if let Some(cursor) = &cursor {
+            query.push(("cursor".to_string(), cursor.clone()));
+        }
+        if let Some(offset) = offset {
+            query.push(("offset".to_string(), offset.to_string()));
+        }
+        let response = client.execute(request.method, request.url, &query, request.body.clone())?;
+        if !(200..300).contains(&response.status)
+            || response
+                .body
+                .get("results")
+                .and_then(Value::as_array)
+                .is_none()
+        {
+            return Ok(Some(response));
+        }
+
+        if is_offset_envelope(&response.body) {
+            let next = next_offset(&response.body, offset.unwrap_or(0), per_page);
+            if page >= range.skip {
+                taken += 1;
+                visit(page + 1, response)?;
+            }
+            page += 1;
+            let position = next.map(Position::Offset);
+            save_checkpoint(checkpoint, position, page)?;
+            let done = range.take.is_some_and(|take| taken >= take);
+            if done || next.is_none() {
+                return Ok(None);
+            }
+            offset = next;
+            continue;
+        }
+
+        let next = next_cursor(&response.body);
+        if page >= range.skip {
+            taken += 1;
+            visit(page + 1, response)?;
+        }
+        page += 1;
+
+        save_checkpoint(checkpoint, next.clone().map(Position::Cursor), page)?;
+        let done = range.take.is_some_and(|take| taken >= take);
+        if done || next.is_none() {
+            return Ok(None);
+        }
+        cursor = next;
+    }
+}
+
+/// Records where the next page starts, or removes the checkpoint after the last page.
+fn save_checkpoint(
+    checkpoint: Option<&Checkpoint>,
+    next: Option<Position>,
+    page: usize,
+) -> Result<()> {
+    match (checkpoint, next) {
+        (Some(checkpoint), Some(position)) => checkpoint.save(&Resume { position, page }),
+        (Some(checkpoint), None) => checkpoint.clear(),
+        (None, _) => Ok(()),
+    }
+}
+
+/// Outcome of `save_pages`.
+#[derive(Debug)]
+pub enum SavedPages {
+    Saved {
+        files: Vec<PathBuf>,
+        items: usize,
+    },
+    /// Non-paginated or error response, returned as-is.
+    Response(ResponseData),
+}
+
+/// Writes each page body (results plus its cursor/count metadata) to
+/// `dir/page-NNNN.json`, numbered by absolute page so `--pages` runs can resume.
+pub fn save_pages(
+    client: &HttpClient,
+    request: PageRequest,
+    range: PageRange,
+    checkpoint: Option<&Checkpoint>,
+    dir: &Path,
+    on_page: &mut dyn FnMut(PageProgress),
+) -> Result<SavedPages> {
+    fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let mut files = Vec::new();
+    let mut items = 0;
+    let mut visit = |page: usize, response: ResponseData| -> Result<()> {
+        let path = dir.join(format!("page-{page:04}.json"));
+        let text = serde_json::to_string_pretty(&response.body)?;
+        fs::write(&path, text).with_context(|| format!("write {}", path.display()))?;
+        items += response
+            .body
+            .get("results")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        files.push(path);
+        on_page(PageProgress {
+            pages: files.len(),
+            items,
+            total_count: total_count(&response.body),
+        });
+        Ok(())
+    };
+    match walk_pages(client, request, range, checkpoint, &mut visit)? {
+        Some(response) => Ok(SavedPages::Response(response)),
+        None => Ok(SavedPages::Saved { files, items }),
+    }
+}
+
+/// Upper bound on items `fetch_pages` pre-allocates from a reported total.
+const PRESIZE_LIMIT: u64 = 100_000;
+
+/// Items across all pages: `total_count`, or `count` on offset envelopes. (On cursor
+/// envelopes `count` is the size of the current page.)
+pub fn total_count(body: &Value) -> Option<u64> {
+    body.get("total_count").and_then(Value::as_u64).or_else(|| {
+        is_offset_envelope(body)
+            .then(|| body.get("count").and_then(Value::as_u64))
+            .flatten()
+    })
+}
+
+/// Older endpoints page with `offset`: no `next_cursor`, but a `count` total or a
+/// `next` link. Bodies with neither are treated as a single page.
+fn is_offset_envelope(body: &Value) -> bool {
+    body.get("next_cursor").is_none() && (body.get("count").is_some() || body.get("next").is_some())
+}
+
+/// Advances by `per_page` (or the page size when unset) until a short or empty page,
+/// a null `next`, or `count` items.
+fn next_offset(body: &Value, offset: u64, per_page: Option<u64>) -> Option<u64> {
+    let len = body.get("results").and_then(Value::as_array)?.len() as u64;
+    let step = per_page.unwrap_or(len);
+    if len == 0 || len < step || body.get("next").is_some_and(Value::is_null) {
+        return None;
+    }
+    let next = offset + step;
+    match body.get("count").and_then(Value::as_u64) {
+        Some(count) if next >= count => None,
+        _ => Some(next),
+    }
+}
+
+fn query_number(query: &[(String, String)], key: &str) -> Option<u64> {
+    query
+        .iter()
+        .rev()
+        .find(|(name, _)| name == key)
+        .and_then(|(_, value)| value.parse().ok())
+}
+
+fn next_cursor(body: &Value) -> Option<String> {
+    if body.get("next_page_results").and_then(Value::as_bool) == Some(false) {
+        return None;
+    }
+    body.get("next_cursor")
+        .and_then(Value::as_str)
+        .filter(|cursor| !cursor.is_empty())
+        .map(str::to_string)
+}
+
+/// Pagination fields present in a Plane list envelope, for `--raw` output.
+pub fn summary(body: &Value) -> Option<Value> {
+    let map = body.as_object()?;
+    let picked: Map<String, Value> = ["next_cursor", "prev_cursor", "total_count", "count"]
+        .into_iter()
+        .filter_map(|key| map.get(key).map(|value| (key.to_string(), value.clone())))
+        .collect();
+    (!picked.is_empty()).then_some(Value::Object(picked))
+}
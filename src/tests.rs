@@ -1,24 +1,18 @@
 use super::*;
+use plane::{is_workspace_param, matches_workspace_param};
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 fn find_subcommand<'a>(cmd: &'a Command, name: &str) -> Option<&'a Command> {
     cmd.get_subcommands().find(|sub| sub.get_name() == name)
 }
 
 fn path_tokens(path: &str) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut remaining = path;
-    while let Some(start) = remaining.find('<') {
-        if let Some(end) = remaining[start + 1..].find('>') {
-            let token = &remaining[start + 1..start + 1 + end];
-            let name = token.split(':').nth(1).unwrap_or(token);
-            out.push(name.to_string());
-            remaining = &remaining[start + 1 + end + 1..];
-        } else {
-            break;
-        }
-    }
-    out
+    command_tree::path_tokens(path)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|token| token.name.to_string())
+        .collect()
 }
 
 fn dummy_value_for_param(name: &str) -> String {
@@ -78,7 +72,7 @@ fn command_tree_has_unique_ops() {
 #[test]
 fn cli_includes_all_ops() {
     let tree = command_tree::load_command_tree();
-    let cli = build_cli(&tree);
+    let cli = build_cli(&tree, false);
     for res in &tree.resources {
         let res_cmd = find_subcommand(&cli, &res.name).expect("missing resource");
         for op in &res.ops {
@@ -88,6 +82,25 @@ fn cli_includes_all_ops() {
     }
 }
 
+#[test]
+fn find_op_accepts_unique_prefixes() {
+    let tree = command_tree::load_command_tree();
+    let op = find_op(&tree, "work-i", "list").expect("prefix match");
+    assert_eq!(
+        op.path,
+        find_op(&tree, "work-item", "list").expect("exact").path
+    );
+
+    let err = find_op(&tree, "m", "list").expect_err("ambiguous");
+    let msg = err.to_string();
+    assert!(msg.contains("member") && msg.contains("module"), "{msg}");
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "proj", "list", "--slug", "ckrwl"])
+        .expect("parse prefix");
+    assert_eq!(matches.subcommand_name(), Some("project"));
+}
+
 #[test]
 fn build_path_substitutes_tokens() {
     let tree = command_tree::load_command_tree();
@@ -97,7 +110,7 @@ fn build_path_substitutes_tokens() {
             for param in &op.params {
                 params.insert(param.name.clone(), dummy_value_for_param(&param.name));
             }
-            let path = build_path(&op.path, &params).expect("build path");
+            let path = build_path(op, &params).expect("build path");
             assert!(!path.contains('<'), "unsubstituted token {}", path);
             assert!(!path.contains('>'), "unsubstituted token {}", path);
         }
@@ -110,6 +123,22 @@ fn join_url_preserves_trailing_slash() {
     assert_eq!(url, "https://example.com/api/v1/users/");
 }
 
+#[test]
+fn join_url_skips_base_path_already_in_path() {
+    let url = join_url("https://example.com", "/api/v1", "workspaces/ckrwl/");
+    assert_eq!(url, "https://example.com/api/v1/workspaces/ckrwl/");
+
+    let url = join_url(
+        "https://example.com",
+        "/api/v1",
+        "/api/v1/workspaces/ckrwl/",
+    );
+    assert_eq!(url, "https://example.com/api/v1/workspaces/ckrwl/");
+
+    let url = join_url("https://example.com", "/api/v1", "api/v10/users/");
+    assert_eq!(url, "https://example.com/api/v1/api/v10/users/");
+}
+
 #[test]
 fn split_base_url_variants() {
     let (api, path) = split_base_url("https://example.com", "/api/v1").expect("split");
@@ -121,6 +150,24 @@ fn split_base_url_variants() {
     assert_eq!(path, "/ckrwl");
 }
 
+#[test]
+fn api_base_flags_override_env() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "--api-url",
+            "https://staging.example.com",
+            "--base-path",
+            "/api/v2",
+            "tree",
+        ])
+        .expect("parse");
+    let (api_url, base_path) = resolve_api_base(&tree, &matches).expect("resolve");
+    assert_eq!(api_url, "https://staging.example.com");
+    assert_eq!(base_path, "/api/v2");
+}
+
 #[test]
 fn parse_query_pair_validation() {
     let (k, v) = parse_query_pair("a=b").expect("parse");
@@ -131,6 +178,61 @@ fn parse_query_pair_validation() {
     assert!(parse_query_pair("ab").is_err());
 }
 
+#[test]
+fn query_values_are_encoded_as_single_param() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "request", "GET", "issues/", "--query", "q=a b&c=d"])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    let query = build_query_params(sub, &[]).expect("query");
+
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let request = client
+        .build_request("GET", "https://example.com/api/v1/issues/", &query, None)
+        .expect("build request")
+        .build()
+        .expect("request");
+
+    let raw = request.url().query().expect("query string");
+    assert!(!raw.contains('&'), "unencoded separator in {raw}");
+    let pairs: Vec<(String, String)> = request.url().query_pairs().into_owned().collect();
+    assert_eq!(pairs, vec![("q".to_string(), "a b&c=d".to_string())]);
+}
+
+#[test]
+fn repeated_fields_are_joined_and_deduplicated() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane", "request", "GET", "issues/", "--fields", "id,name", "--fields", "name",
+            "--fields", "state",
+        ])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    let query = build_query_params(sub, &[]).expect("query");
+    assert_eq!(
+        query,
+        vec![("fields".to_string(), "id,name,state".to_string())]
+    );
+}
+
+#[test]
+fn page_range_parsing() {
+    let range = PageRange::parse("2:4").expect("parse");
+    assert_eq!(range.skip, 1);
+    assert_eq!(range.take, Some(3));
+    assert!(PageRange::parse("0:2").is_err());
+    assert!(PageRange::parse("3:2").is_err());
+    assert!(PageRange::parse("3").is_err());
+
+    let tree = command_tree::load_command_tree();
+    let result = build_cli(&tree, false).try_get_matches_from([
+        "plane", "request", "GET", "issues/", "--pages", "2:4", "--cursor", "abc",
+    ]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn workspace_param_detection() {
     assert!(is_workspace_param("slug"));
@@ -139,3 +241,2381 @@ fn workspace_param_detection() {
     assert!(is_workspace_param("workspaceSlug"));
     assert!(!is_workspace_param("project_id"));
 }
+
+#[test]
+fn error_json_includes_http_context() {
+    let err: anyhow::Error = HttpError {
+        status: 404,
+        url: "https://example.com/api/v1/users/me/".to_string(),
+        body: json!({"error": "Not found"}),
+    }
+    .into();
+    let value = error_json(&err);
+    assert_eq!(value["error"], "Not found");
+    assert_eq!(value["status"], 404);
+    assert_eq!(value["url"], "https://example.com/api/v1/users/me/");
+
+    let value = error_json(&anyhow!("PLANE_API_KEY missing"));
+    assert_eq!(value["error"], "PLANE_API_KEY missing");
+    assert!(value["status"].is_null());
+}
+
+#[test]
+fn quiet_flag_is_global() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "request", "GET", "/users/me/", "-q"])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    assert!(sub.get_flag("quiet"));
+}
+
+#[test]
+fn api_key_flag_beats_file() {
+    let path = env::temp_dir().join(format!("plane-cli-key-{}", std::process::id()));
+    fs::write(&path, "from-file\n").expect("write key file");
+    let path = path.to_string_lossy().to_string();
+    let tree = command_tree::load_command_tree();
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--api-key-file", &path, "tree"])
+        .expect("parse");
+    assert_eq!(resolve_api_key(&matches).expect("key"), "from-file");
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "--api-key",
+            "from-flag",
+            "--api-key-file",
+            &path,
+            "tree",
+        ])
+        .expect("parse");
+    assert_eq!(resolve_api_key(&matches).expect("key"), "from-flag");
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn api_key_command_output_and_errors() {
+    assert_eq!(
+        run_api_key_command("echo '  secret  '").expect("key"),
+        "secret"
+    );
+    assert!(run_api_key_command("exit 3").is_err());
+    assert!(run_api_key_command("true").is_err());
+}
+
+#[test]
+fn env_file_loads_missing_vars() {
+    let path = env::temp_dir().join(format!("plane-cli-env-{}", std::process::id()));
+    fs::write(&path, "PLANE_CLI_DOTENV_TEST=from-file\n").expect("write env file");
+    let path = path.to_string_lossy().to_string();
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--env-file", &path, "tree"])
+        .expect("parse");
+
+    load_dotenv(&matches).expect("load env file");
+    assert_eq!(env::var("PLANE_CLI_DOTENV_TEST").expect("var"), "from-file");
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn alias_parse_and_substitute() {
+    let aliases = alias::parse(
+        r#"
+open = ["work-item", "list", "--project-id", "$1", "--per-page", "100"]
+me = "user me-list"
+"#,
+    )
+    .expect("parse aliases");
+    assert_eq!(aliases["me"], vec!["user", "me-list"]);
+
+    let args = vec!["PROJ".to_string(), "--pretty".to_string()];
+    let expanded = alias::substitute(&aliases["open"], &args).expect("substitute");
+    assert_eq!(
+        expanded,
+        vec![
+            "work-item",
+            "list",
+            "--project-id",
+            "PROJ",
+            "--per-page",
+            "100",
+            "--pretty"
+        ]
+    );
+    assert!(alias::substitute(&aliases["open"], &[]).is_err());
+    assert!(alias::parse("bad = 1").is_err());
+}
+
+#[test]
+fn output_file_creates_parent_dirs() {
+    let dir = env::temp_dir().join(format!("plane-cli-out-{}", std::process::id()));
+    let path = dir.join("nested").join("body.json");
+    let path_str = path.to_string_lossy().to_string();
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--output-file", &path_str, "tree"])
+        .expect("parse");
+
+    write_body(&matches, "{\"id\":1}").expect("write body");
+    assert_eq!(fs::read_to_string(&path).expect("read"), "{\"id\":1}\n");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn auto_idempotency_only_for_post() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--auto-idempotency", "tree"])
+        .expect("parse");
+    let key = idempotency_key(&matches, "POST").expect("key for POST");
+    assert_eq!(key.len(), 36);
+    assert!(idempotency_key(&matches, "GET").is_none());
+
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_header("Idempotency-Key", key.clone());
+    let request = client
+        .build_request("POST", "https://example.com/api/v1/issues/", &[], None)
+        .expect("build request")
+        .build()
+        .expect("request");
+    assert_eq!(request.headers()["idempotency-key"], key.as_str());
+}
+
+#[test]
+fn compress_only_large_bodies() {
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_compression(true);
+    let url = "https://example.com/api/v1/issues/";
+
+    let small = json!({"name": "Fix login"});
+    let request = client
+        .build_request("POST", url, &[], Some(&small))
+        .expect("build request")
+        .build()
+        .expect("request");
+    assert!(request.headers().get("content-encoding").is_none());
+
+    let large = json!({"description": "x".repeat(http::COMPRESS_THRESHOLD * 2)});
+    let request = client
+        .build_request("POST", url, &[], Some(&large))
+        .expect("build request")
+        .build()
+        .expect("request");
+    assert_eq!(request.headers()["content-encoding"], "gzip");
+    let sent = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .expect("body");
+    assert!(sent.len() < http::COMPRESS_THRESHOLD);
+}
+
+#[test]
+fn table_columns_resolve_dotted_paths() {
+    let columns = output::parse_columns("id:ID,name:Name,state.name:State").expect("columns");
+    assert_eq!(columns[2].path, "state.name");
+    assert_eq!(columns[2].header, "State");
+
+    let body = json!({"results": [
+        {"id": 1, "name": "Fix login", "state": {"name": "Todo"}},
+        {"id": 22, "name": "Ship", "state": null},
+    ]});
+    let table = output::render_table(&body, Some(&columns));
+    assert_eq!(table, "ID  Name       State\n1   Fix login  Todo\n22  Ship");
+    assert!(output::parse_columns(":x").is_err());
+}
+
+#[test]
+fn csv_output_quotes_and_serializes_nested() {
+    let columns = output::parse_columns("id,name,labels").expect("columns");
+    let body = json!([
+        {"id": 1, "name": "Fix, login", "labels": ["bug"]},
+        {"id": 2, "name": "Say \"hi\""},
+    ]);
+    let csv = output::render_delimited(&body, Some(&columns), b',').expect("csv");
+    assert_eq!(
+        csv,
+        "id,name,labels\n1,\"Fix, login\",\"[\"\"bug\"\"]\"\n2,\"Say \"\"hi\"\"\","
+    );
+
+    let tsv = output::render_delimited(&body, Some(&columns[..1]), b'\t').expect("tsv");
+    assert_eq!(tsv, "id\n1\n2");
+    assert!(output::render_delimited(&json!({"id": 1}), None, b',').is_err());
+}
+
+#[test]
+fn colorized_pretty_matches_plain_layout() {
+    let value = json!({
+        "id": 1,
+        "name": "Fix \"login\"",
+        "done": false,
+        "parent": null,
+        "labels": [],
+        "meta": {},
+        "nested": {"items": [1.5, "two", true]},
+    });
+    let colored = output::colorize_pretty(&value);
+    assert!(colored.contains("\x1b["));
+
+    let mut stripped = String::new();
+    let mut chars = colored.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            for skip in chars.by_ref() {
+                if skip == 'm' {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(ch);
+        }
+    }
+    assert_eq!(
+        stripped,
+        serde_json::to_string_pretty(&value).expect("pretty")
+    );
+}
+
+#[test]
+fn template_iterates_rows_and_reports_errors() {
+    let body = json!({"results": [{"id": 1, "name": "A & B"}, {"id": 2, "name": "C"}]});
+    let text = output::render_template("{{#each (rows this)}}{{id}}={{name}}\n{{/each}}", &body)
+        .expect("render");
+    assert_eq!(text, "1=A & B\n2=C\n");
+
+    let err = output::render_template("line one\n{{#each}}", &body).expect_err("bad template");
+    assert!(err.to_string().contains("Template error"), "{err}");
+}
+
+#[test]
+fn accept_header_can_be_overridden() {
+    let url = "https://example.com/api/v1/exports/";
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let request = client
+        .build_request("GET", url, &[], None)
+        .expect("build request")
+        .build()
+        .expect("request");
+    assert_eq!(request.headers()["accept"], "application/json");
+
+    let client = client.with_accept("text/csv");
+    let request = client
+        .build_request("GET", url, &[], None)
+        .expect("build request")
+        .build()
+        .expect("request");
+    let accepts: Vec<_> = request.headers().get_all("accept").iter().collect();
+    assert_eq!(accepts, vec!["text/csv"]);
+}
+
+#[test]
+fn pagination_summary_tolerates_missing_keys() {
+    let body = json!({"results": [], "next_cursor": "100:1:0", "total_count": 250});
+    assert_eq!(
+        pagination::summary(&body),
+        Some(json!({"next_cursor": "100:1:0", "total_count": 250}))
+    );
+    assert_eq!(pagination::summary(&json!({"id": 1})), None);
+    assert_eq!(pagination::summary(&json!([1, 2])), None);
+}
+
+fn op_with_query(names: &[&str]) -> Operation {
+    Operation {
+        name: "list".to_string(),
+        method: "GET".to_string(),
+        path: "workspaces/<str:slug>/projects/".to_string(),
+        deprecated: false,
+        params: vec![Param {
+            name: "slug".to_string(),
+            flag: "slug".to_string(),
+        }],
+        query: names
+            .iter()
+            .map(|name| QueryParam {
+                name: name.to_string(),
+                kind: None,
+            })
+            .collect(),
+        docs_url: None,
+        default_query: Vec::new(),
+        trailing_slash: None,
+        default_accept: None,
+        min_api_version: None,
+        max_api_version: None,
+        expand: Vec::new(),
+    }
+}
+
+#[test]
+fn unknown_query_keys_warn_or_fail() {
+    let tree = command_tree::load_command_tree();
+    let op = op_with_query(&["state", "priority"]);
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "request", "GET", "x/", "--query", "stat=open"])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    assert!(check_query_keys(&op, sub, &[]).is_ok());
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "request",
+            "GET",
+            "x/",
+            "--query",
+            "stat=open",
+            "--strict-query",
+        ])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    let err = check_query_keys(&op, sub, &[]).expect_err("strict");
+    assert!(err.to_string().contains("stat"), "{err}");
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "request",
+            "GET",
+            "x/",
+            "--query",
+            "state=open",
+            "--strict-query",
+        ])
+        .expect("parse");
+    let (_, sub) = matches.subcommand().expect("subcommand");
+    assert!(check_query_keys(&op, sub, &[]).is_ok());
+    assert!(check_query_keys(&op_with_query(&[]), sub, &[]).is_ok());
+}
+
+#[test]
+fn query_file_keys_are_checked_too() {
+    let path = env::temp_dir().join(format!("plane-query-keys-{}.txt", std::process::id()));
+    fs::write(&path, "# filters\nstate=open\nprioirty=high\n").unwrap();
+    let path = path.to_string_lossy().to_string();
+    let tree = command_tree::load_command_tree();
+    let op = op_with_query(&["state", "priority"]);
+    let parse = |extra: &[&str]| {
+        let mut args = vec!["plane", "request", "GET", "x/", "--query-file", &path];
+        args.extend_from_slice(extra);
+        build_cli(&tree, false)
+            .try_get_matches_from(args)
+            .expect("parse")
+    };
+
+    let lenient = parse(&[]);
+    let (_, sub) = lenient.subcommand().expect("subcommand");
+    let query_file = read_query_file(sub).expect("query file");
+    assert!(check_query_keys(&op, sub, &query_file).is_ok());
+
+    let strict = parse(&["--strict-query"]);
+    let (_, sub) = strict.subcommand().expect("subcommand");
+    let err = check_query_keys(&op, sub, &query_file).expect_err("strict");
+    assert!(err.to_string().contains("prioirty"), "{err}");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn bool_query_params_become_flags() {
+    let mut tree = command_tree::load_command_tree();
+    let mut op = op_with_query(&["state"]);
+    op.query.push(QueryParam {
+        name: "is_active".to_string(),
+        kind: Some("boolean".to_string()),
+    });
+    tree.resources[0].ops.push(op.clone());
+    let res = tree.resources[0].name.clone();
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", &res, "list", "--slug", "ckrwl", "--is-active"])
+        .expect("parse");
+    let (_, res_matches) = matches.subcommand().expect("resource");
+    let (_, op_matches) = res_matches.subcommand().expect("op");
+    assert_eq!(
+        bool_query_params(&op, op_matches),
+        vec![("is_active".to_string(), "true".to_string())]
+    );
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            &res,
+            "list",
+            "--slug",
+            "ckrwl",
+            "--is-active=false",
+        ])
+        .expect("parse");
+    let (_, res_matches) = matches.subcommand().expect("resource");
+    let (_, op_matches) = res_matches.subcommand().expect("op");
+    assert_eq!(
+        bool_query_params(&op, op_matches),
+        vec![("is_active".to_string(), "false".to_string())]
+    );
+}
+
+#[test]
+fn docs_url_prefers_tree_value() {
+    let mut op = op_with_query(&[]);
+    assert_eq!(
+        docs_url("project", &op),
+        "https://developers.plane.so/api-reference/project/overview"
+    );
+    op.docs_url = Some("https://example.com/docs/list".to_string());
+    assert_eq!(docs_url("project", &op), "https://example.com/docs/list");
+}
+
+#[test]
+fn user_agent_override_and_append() {
+    let tree = command_tree::load_command_tree();
+    let default = http::default_user_agent();
+    assert!(default.starts_with("plane-cli/"));
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--user-agent", "sync-bot/2", "tree"])
+        .expect("parse");
+    assert_eq!(resolve_user_agent(&matches), "sync-bot/2");
+
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "--user-agent", "+sync-bot/2", "tree"])
+        .expect("parse");
+    assert_eq!(
+        resolve_user_agent(&matches),
+        format!("{default} sync-bot/2")
+    );
+}
+
+#[test]
+fn transport_errors_are_classified_for_retry() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    drop(listener);
+    let client = reqwest::blocking::Client::new();
+    let refused = client
+        .get(format!("http://{addr}/"))
+        .send()
+        .expect_err("connection refused");
+    assert!(http::is_retryable_error(&refused, true));
+    assert!(http::is_retryable_error(&refused, false));
+
+    let invalid = client.get("not a url").send().expect_err("invalid url");
+    assert!(!http::is_retryable_error(&invalid, true));
+}
+
+#[test]
+fn retry_recovers_from_dropped_connection() {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let server = std::thread::spawn(move || {
+        drop(listener.accept().expect("first accept"));
+        let (mut stream, _) = listener.accept().expect("second accept");
+        let mut buf = [0u8; 4096];
+        let _ = stream.read(&mut buf);
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 11\r\n\r\n{\"ok\":true}")
+            .expect("write");
+    });
+
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_retries(2);
+    let response = client
+        .execute("GET", &format!("http://{addr}/"), &[], None)
+        .expect("retried request");
+    server.join().expect("server");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, serde_json::json!({"ok": true}));
+}
+
+/// Answers each connection with the next status in `statuses` until a short deadline;
+/// returns how many requests arrived.
+fn serve_statuses(statuses: Vec<u16>) -> (String, std::thread::JoinHandle<usize>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/", listener.local_addr().expect("addr"));
+    listener.set_nonblocking(true).expect("nonblocking");
+    let server = std::thread::spawn(move || {
+        let deadline = std::time::Instant::now() + Duration::from_millis(1500);
+        let mut seen = 0;
+        while std::time::Instant::now() < deadline {
+            match listener.accept() {
+                Ok((mut stream, _)) => {
+                    stream.set_nonblocking(false).expect("blocking");
+                    let _ = stream.read(&mut [0u8; 4096]);
+                    let status = statuses.get(seen).copied().unwrap_or(200);
+                    let response = format!(
+                        "HTTP/1.1 {status} X\r\nconnection: close\r\ncontent-length: 2\r\n\r\n{{}}"
+                    );
+                    stream.write_all(response.as_bytes()).expect("write");
+                    seen += 1;
+                }
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        seen
+    });
+    (url, server)
+}
+
+#[test]
+fn post_is_not_resent_after_a_server_error() {
+    let client = || {
+        HttpClient::new("test".to_string(), ClientOptions::default())
+            .expect("client")
+            .with_retries(1)
+    };
+    let body = Some(json!({"name": "x"}));
+
+    let (url, server) = serve_statuses(vec![500]);
+    let response = client()
+        .execute("POST", &url, &[], body.clone())
+        .expect("post");
+    assert_eq!(response.status, 500);
+    assert_eq!(server.join().expect("server"), 1);
+
+    // 503 means the request was turned away, so resending is safe.
+    let (url, server) = serve_statuses(vec![503]);
+    let response = client().execute("POST", &url, &[], body).expect("post");
+    assert_eq!(response.status, 200);
+    assert_eq!(server.join().expect("server"), 2);
+
+    let (url, server) = serve_statuses(vec![500]);
+    let response = client().execute("GET", &url, &[], None).expect("get");
+    assert_eq!(response.status, 200);
+    assert_eq!(server.join().expect("server"), 2);
+}
+
+#[test]
+fn post_is_not_replayed_after_a_dropped_connection() {
+    use std::io::Read;
+    // Reads each request, then hangs up without answering; returns how many arrived.
+    let serve = || {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/", listener.local_addr().expect("addr"));
+        listener.set_nonblocking(true).expect("nonblocking");
+        let server = std::thread::spawn(move || {
+            let deadline = std::time::Instant::now() + Duration::from_millis(1500);
+            let mut seen = 0;
+            while std::time::Instant::now() < deadline {
+                match listener.accept() {
+                    Ok((mut stream, _)) => {
+                        stream.set_nonblocking(false).expect("blocking");
+                        let _ = stream.read(&mut [0u8; 4096]);
+                        seen += 1;
+                    }
+                    Err(_) => std::thread::sleep(Duration::from_millis(10)),
+                }
+            }
+            seen
+        });
+        (url, server)
+    };
+    let body = Some(json!({"name": "x"}));
+
+    let (url, server) = serve();
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_retries(2);
+    assert!(client.execute("POST", &url, &[], body.clone()).is_err());
+    assert_eq!(server.join().expect("server"), 1);
+
+    // An idempotency key makes the replay safe.
+    let (url, server) = serve();
+    let keyed = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_retries(1)
+        .with_header("Idempotency-Key", "k1");
+    assert!(keyed.execute("POST", &url, &[], body).is_err());
+    assert_eq!(server.join().expect("server"), 2);
+}
+
+#[test]
+fn clock_skew_from_date_header() {
+    use std::time::{Duration, SystemTime};
+    let date = "Sun, 06 Nov 1994 08:49:37 GMT";
+    let server = httpdate::parse_http_date(date).unwrap();
+    assert_eq!(http::clock_skew(date, server), Some(0));
+    assert_eq!(
+        http::clock_skew(date, server + Duration::from_secs(47)),
+        Some(-47)
+    );
+    assert_eq!(
+        http::clock_skew(date, server - Duration::from_secs(90)),
+        Some(90)
+    );
+    assert_eq!(http::clock_skew("yesterday", SystemTime::now()), None);
+}
+
+#[test]
+fn retry_on_parses_codes_and_ranges() {
+    assert_eq!(
+        http::parse_status_list("429, 500-502,522").unwrap(),
+        vec![429, 500, 501, 502, 522]
+    );
+    assert!(http::parse_status_list("abc").is_err());
+    assert!(http::parse_status_list("504-500").is_err());
+    assert!(http::parse_status_list("99").is_err());
+    assert!(http::parse_status_list("500,").is_err());
+}
+
+#[test]
+fn retry_timeout_bounds_retries_by_wall_clock() {
+    use std::time::Duration;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    drop(listener);
+
+    // Backoff is 500ms then 1s: the first retry fits in 800ms, the second does not.
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_retries(u32::MAX)
+        .with_retry_timeout(Some(Duration::from_millis(800)));
+    let started = std::time::Instant::now();
+    let err = client
+        .execute("GET", &format!("http://{addr}/"), &[], None)
+        .expect_err("refused");
+    assert!(format!("{err:#}").contains("send request"));
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(500), "{elapsed:?}");
+    assert!(elapsed < Duration::from_millis(1500), "{elapsed:?}");
+
+    let tree = command_tree::load_command_tree();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "tree"];
+        argv.extend_from_slice(args);
+        build_cli(&tree, false).try_get_matches_from(argv).unwrap()
+    };
+    assert_eq!(retry_count(&parse(&[])), 0);
+    assert_eq!(retry_count(&parse(&["--retry-timeout", "2m"])), u32::MAX);
+    assert_eq!(
+        retry_count(&parse(&["--retry-timeout", "2m", "--retry", "3"])),
+        3
+    );
+}
+
+#[test]
+fn request_url_shapes() {
+    let api = "https://api.plane.so";
+    let base = "/api/v1";
+    assert_eq!(
+        request_url(api, base, "https://other.host/x/", false),
+        "https://other.host/x/"
+    );
+    assert_eq!(
+        request_url(api, base, "/users/me/", false),
+        "https://api.plane.so/api/v1/users/me/"
+    );
+    assert_eq!(
+        request_url(api, base, "users/me/", false),
+        "https://api.plane.so/api/v1/users/me/"
+    );
+    assert_eq!(
+        request_url(api, base, "/health/", true),
+        "https://api.plane.so/health/"
+    );
+    assert_eq!(
+        request_url(api, base, "users/me/", true),
+        "https://api.plane.so/api/v1/users/me/"
+    );
+}
+
+#[test]
+fn workspace_params_can_be_augmented() {
+    let param = |name: &str, flag: &str| Param {
+        name: name.to_string(),
+        flag: flag.to_string(),
+    };
+    assert!(matches_workspace_param(
+        &param("workspaceSlug", "workspace-slug"),
+        ""
+    ));
+    assert!(matches_workspace_param(
+        &param("ws_slug", "workspace-slug"),
+        ""
+    ));
+    assert!(!matches_workspace_param(&param("ws", "ws"), ""));
+    assert!(matches_workspace_param(&param("ws", "ws"), "team, ws"));
+    assert!(!matches_workspace_param(
+        &param("project_id", "project-id"),
+        "ws,"
+    ));
+}
+
+#[test]
+fn project_param_detection() {
+    assert!(is_project_param("project_id"));
+    assert!(is_project_param("projectId"));
+    assert!(!is_project_param("project_identifier"));
+    assert!(!is_project_param("issue_id"));
+}
+
+#[test]
+fn manifest_lists_every_operation_with_typed_params() {
+    let tree = command_tree::load_command_tree();
+    let manifest = manifest::build(&tree);
+    assert_eq!(manifest.manifest_version, manifest::MANIFEST_VERSION);
+    assert_eq!(manifest.tree_version, tree.version);
+    let total: usize = tree.resources.iter().map(|res| res.ops.len()).sum();
+    assert_eq!(manifest.operations.len(), total);
+
+    let op = manifest
+        .operations
+        .iter()
+        .find(|op| op.params.iter().any(|param| param.name == "project_id"))
+        .expect("project-scoped op");
+    let project = op
+        .params
+        .iter()
+        .find(|param| param.name == "project_id")
+        .unwrap();
+    assert_eq!(project.kind, "uuid");
+    assert_eq!(project.location, "path");
+    assert!(project.required);
+    let slug = op.params.iter().find(|param| param.name == "slug").unwrap();
+    assert_eq!(slug.kind, "string");
+}
+
+#[test]
+fn schema_splits_params_and_uses_placeholders() {
+    let tree = command_tree::load_command_tree();
+    let op = find_op(&tree, "project", "archive").unwrap();
+    let url = join_url(
+        "https://api.plane.so",
+        "/api/v1",
+        &placeholder_path(op).unwrap(),
+    );
+    let schema = serde_json::to_value(manifest::schema("project", op, url)).unwrap();
+    assert_eq!(
+        schema["example_url"],
+        "https://api.plane.so/api/v1/workspaces/{slug}/projects/{project_id}/archive/"
+    );
+    assert_eq!(schema["path_params"][1]["type"], "uuid");
+    assert_eq!(schema["query_params"], json!([]));
+    assert_eq!(schema["body"], Value::Null);
+}
+
+#[test]
+fn http_error_summary_truncates_long_bodies() {
+    let err = |body: Value| HttpError {
+        status: 400,
+        url: "https://example.com/api/v1/x/".to_string(),
+        body,
+    };
+
+    let long = "x".repeat(600);
+    let summary = err(Value::String(long)).summary(500);
+    assert!(summary.starts_with(&"x".repeat(500)));
+    assert!(summary.ends_with("(truncated; use --raw for the full body)"));
+
+    let array = Value::Array((0..200).map(|idx| json!({"field": idx})).collect());
+    let summary = err(array).summary(500);
+    assert!(summary.chars().count() < 560, "{summary}");
+    assert!(summary.contains("--raw"));
+
+    let mut big = serde_json::Map::new();
+    big.insert("error".to_string(), json!("name is required"));
+    for idx in 0..100 {
+        big.insert(format!("k{idx}"), json!("v".repeat(20)));
+    }
+    assert_eq!(err(Value::Object(big)).summary(500), "name is required");
+    assert_eq!(err(json!("short")).summary(0), "short");
+
+    let fields = json!({"name": ["required"]});
+    assert_eq!(err(fields.clone()).summary(500), r#"{"name":["required"]}"#);
+    assert_eq!(
+        err(fields).pretty_summary(500),
+        "{\n  \"name\": [\n    \"required\"\n  ]\n}"
+    );
+    let wide = json!({"detail": ["x".repeat(600)]});
+    assert!(
+        err(wide)
+            .pretty_summary(500)
+            .ends_with("(truncated; use --raw for the full body)")
+    );
+}
+
+#[test]
+fn timings_are_recorded_per_request() {
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_timings(true);
+    assert!(client.timing_summary().is_none());
+    for _ in 0..3 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = std::thread::spawn(move || {
+            use std::io::{Read, Write};
+            let (mut stream, _) = listener.accept().expect("accept");
+            let _ = stream.read(&mut [0u8; 4096]);
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 2\r\n\r\n{}")
+                .expect("write");
+        });
+        client
+            .execute("GET", &format!("http://{addr}/"), &[], None)
+            .expect("request");
+        server.join().expect("server");
+    }
+    let summary = client.timing_summary().expect("summary");
+    assert!(summary.starts_with("timings: 3 requests"), "{summary}");
+    assert!(summary.ends_with("6 bytes"), "{summary}");
+}
+
+#[test]
+fn timings_from_several_workers_summarize_together() {
+    let timing = |ms: u64| http::Timing {
+        ttfb: Duration::from_millis(ms / 2),
+        total: Duration::from_millis(ms),
+        bytes: 10,
+    };
+    let worker_a = vec![timing(30), timing(10)];
+    let worker_b = vec![timing(50)];
+    assert!(http::summarize_timings(&worker_b).is_none());
+    let merged: Vec<_> = worker_a.into_iter().chain(worker_b).collect();
+    assert_eq!(
+        http::summarize_timings(&merged).as_deref(),
+        Some("timings: 3 requests, min 10ms, median 30ms, max 50ms, 30 bytes")
+    );
+}
+
+#[test]
+fn include_deprecated_unhides_ops_in_help() {
+    let tree = command_tree::load_command_tree();
+    let (resource, op) = tree
+        .resources
+        .iter()
+        .find_map(|res| {
+            res.ops
+                .iter()
+                .find(|op| op.deprecated)
+                .map(|op| (res.name.clone(), op.name.clone()))
+        })
+        .expect("deprecated op");
+
+    let argv: Vec<String> = ["plane", "--include-deprecated", "--help"]
+        .map(String::from)
+        .to_vec();
+    assert!(wants_deprecated(&argv));
+    assert!(!wants_deprecated(&[
+        "plane".to_string(),
+        "--help".to_string()
+    ]));
+
+    let hidden = |cli: &Command| {
+        let res = find_subcommand(cli, &resource).expect("resource");
+        find_subcommand(res, &op).expect("op").is_hide_set()
+    };
+    assert!(hidden(&build_cli(&tree, false)));
+    assert!(!hidden(&build_cli(&tree, true)));
+}
+
+#[test]
+fn apply_plans_op_params_and_body_from_file() {
+    let tree = command_tree::load_command_tree();
+    let doc = json!({
+        "_op": "project create",
+        "_params": {"slug": "acme"},
+        "name": "Roadmap",
+        "identifier": "RM"
+    });
+    let planned = apply::plan(&tree, doc, None, false, None).expect("plan");
+    assert_eq!(planned.method, "POST");
+    assert_eq!(planned.path, "workspaces/acme/projects/");
+    assert_eq!(planned.body, json!({"name": "Roadmap", "identifier": "RM"}));
+
+    let fallback = apply::plan(
+        &tree,
+        json!({"_params": {"slug": "acme"}, "name": "x"}),
+        Some("project create"),
+        false,
+        None,
+    )
+    .expect("--op fallback");
+    assert_eq!(fallback.op, "project create");
+
+    let err = apply::plan(&tree, json!({"name": "x"}), None, false, None).unwrap_err();
+    assert!(err.to_string().contains("_op"), "{err}");
+}
+
+#[test]
+fn diff_reports_field_level_changes() {
+    let remote = json!({
+        "name": "Old",
+        "priority": "low",
+        "state": {"id": "s1", "group": "backlog"},
+        "labels": ["a"],
+        "description": "text"
+    });
+    let local = json!({
+        "name": "New",
+        "state": {"id": "s1", "group": "started"},
+        "labels": ["a"],
+        "estimate": 3,
+        "description": null
+    });
+    let changes = diff::diff(&remote, &local, false);
+    assert_eq!(
+        diff::render(&changes, false),
+        "- description: \"text\"\n+ estimate: 3\n~ name: \"Old\" -> \"New\"\n~ state.group: \"backlog\" -> \"started\""
+    );
+
+    let replaced = diff::diff(&remote, &local, true);
+    assert!(replaced.contains(&diff::Change::Removed {
+        path: "priority".to_string(),
+        value: json!("low"),
+    }));
+    assert!(diff::diff(&remote, &json!({"name": "Old"}), false).is_empty());
+}
+
+#[test]
+fn method_override_tunnels_through_post() {
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_method_override(true);
+    let req = client
+        .build_request("PATCH", "https://example.com/x/", &[], None)
+        .expect("request")
+        .build()
+        .expect("build");
+    assert_eq!(req.method(), reqwest::Method::POST);
+    assert_eq!(req.headers()["x-http-method-override"], "PATCH");
+
+    let req = client
+        .build_request("GET", "https://example.com/x/", &[], None)
+        .expect("request")
+        .build()
+        .expect("build");
+    assert_eq!(req.method(), reqwest::Method::GET);
+    assert!(!req.headers().contains_key("x-http-method-override"));
+}
+
+#[test]
+fn html_error_pages_are_summarized() {
+    let err = HttpError {
+        status: 502,
+        url: "https://example.com/api/v1/x/".to_string(),
+        body: json!("\n<!DOCTYPE html><html><body><h1>502 Bad Gateway</h1></body></html>"),
+    };
+    assert_eq!(
+        err.to_string(),
+        "http 502: received HTML error page, status 502; use --raw to see it"
+    );
+    assert!(!http::looks_like_html(&json!("plain text")));
+    assert!(!http::looks_like_html(&json!({"error": "<b>"})));
+}
+
+#[test]
+fn default_query_is_overridden_by_explicit_params() {
+    let mut op = op_with_query(&[]);
+    assert_eq!(with_default_query(&op, Vec::new()), Vec::new());
+
+    op.default_query = vec![
+        ("per_page".to_string(), "100".to_string()),
+        ("order_by".to_string(), "-created_at".to_string()),
+    ];
+    let op: Operation = serde_json::from_value(serde_json::to_value(&op).unwrap()).unwrap();
+    let query = with_default_query(&op, vec![("per_page".to_string(), "20".to_string())]);
+    assert_eq!(
+        query,
+        vec![
+            ("order_by".to_string(), "-created_at".to_string()),
+            ("per_page".to_string(), "20".to_string()),
+        ]
+    );
+}
+
+/// Answers one request per connection with each body in turn.
+fn serve_json(bodies: Vec<Value>) -> (String, std::thread::JoinHandle<()>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/", listener.local_addr().expect("addr"));
+    let handle = std::thread::spawn(move || {
+        for body in bodies {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let _ = stream.read(&mut [0u8; 4096]);
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).expect("write");
+        }
+    });
+    (url, handle)
+}
+
+#[test]
+fn output_dir_writes_one_file_per_page() {
+    let (url, server) = serve_json(vec![
+        json!({"results": [1, 2], "next_cursor": "10:1:0", "next_page_results": true}),
+        json!({"results": [3], "next_cursor": "10:2:0", "next_page_results": false}),
+    ]);
+    let dir = std::env::temp_dir().join(format!("plane-pages-{}", std::process::id()));
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let request = PageRequest {
+        method: "GET",
+        url: &url,
+        query: &[],
+        body: None,
+        stop: None,
+    };
+    let saved = pagination::save_pages(&client, request, PageRange::all(), None, &dir, &mut |_| {})
+        .expect("save");
+    server.join().expect("server");
+
+    let SavedPages::Saved { files, items } = saved else {
+        panic!("expected saved pages");
+    };
+    assert_eq!(items, 3);
+    assert_eq!(
+        files,
+        vec![dir.join("page-0001.json"), dir.join("page-0002.json")]
+    );
+    let first: Value = serde_json::from_str(&fs::read_to_string(&files[0]).unwrap()).unwrap();
+    assert_eq!(first["next_cursor"], "10:1:0");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn all_pages_follows_cursor_and_offset_envelopes() {
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let fetch = |bodies: Vec<Value>, query: &[(String, String)]| {
+        let (url, server) = serve_json(bodies);
+        let request = PageRequest {
+            method: "GET",
+            url: &url,
+            query,
+            body: None,
+            stop: None,
+        };
+        let response =
+            pagination::fetch_pages(&client, request, PageRange::all(), None, &mut |_| {})
+                .expect("fetch");
+        server.join().expect("server");
+        response.body
+    };
+
+    let cursor = fetch(
+        vec![
+            json!({"results": [1, 2], "count": 2, "next_cursor": "2:1:0", "next_page_results": true}),
+            json!({"results": [3], "count": 1, "next_cursor": "2:2:0", "next_page_results": false}),
+        ],
+        &[],
+    );
+    assert_eq!(cursor, json!([1, 2, 3]));
+
+    // Stops once `count` is reached, without requesting an empty trailing page.
+    let per_page = [("per_page".to_string(), "2".to_string())];
+    let offset = fetch(
+        vec![
+            json!({"results": [1, 2], "count": 4, "next": "?offset=2"}),
+            json!({"results": [3, 4], "count": 4, "next": null}),
+        ],
+        &per_page,
+    );
+    assert_eq!(offset, json!([1, 2, 3, 4]));
+
+    // Without `count`, a null `next` ends it.
+    let linked = fetch(
+        vec![
+            json!({"results": [1], "next": "?offset=1"}),
+            json!({"results": [2], "next": null}),
+        ],
+        &[],
+    );
+    assert_eq!(linked, json!([1, 2]));
+}
+
+#[test]
+fn cursor_file_resumes_and_clears() {
+    let dir = std::env::temp_dir().join(format!("plane-cursor-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checkpoint = Checkpoint::new(dir.join("cursor.json"));
+    assert_eq!(checkpoint.load().unwrap(), None);
+
+    // An earlier run stopped after page 2; only the rest is fetched, numbered from 3.
+    checkpoint
+        .save(&pagination::Resume {
+            position: pagination::Position::Cursor("10:2:0".to_string()),
+            page: 2,
+        })
+        .unwrap();
+    let (url, server) = serve_json(vec![
+        json!({"results": [5], "next_cursor": "10:3:0", "next_page_results": true}),
+        json!({"results": [6], "next_page_results": false}),
+    ]);
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let request = PageRequest {
+        method: "GET",
+        url: &url,
+        query: &[],
+        body: None,
+        stop: None,
+    };
+    let saved = pagination::save_pages(
+        &client,
+        request,
+        PageRange::all(),
+        Some(&checkpoint),
+        &dir,
+        &mut |_| {},
+    )
+    .expect("save");
+    server.join().expect("server");
+
+    let SavedPages::Saved { files, .. } = saved else {
+        panic!("expected saved pages");
+    };
+    assert_eq!(
+        files,
+        vec![dir.join("page-0003.json"), dir.join("page-0004.json")]
+    );
+    assert_eq!(checkpoint.load().unwrap(), None);
+    assert!(!dir.join("cursor.json").exists());
+
+    fs::write(dir.join("cursor.json"), "10:7:0\n").unwrap();
+    assert_eq!(
+        checkpoint.load().unwrap(),
+        Some(pagination::Resume {
+            position: pagination::Position::Cursor("10:7:0".to_string()),
+            page: 0,
+        })
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cursor_file_checkpoints_offset_pagination() {
+    let dir = std::env::temp_dir().join(format!("plane-offset-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checkpoint = Checkpoint::new(dir.join("cursor.json"));
+    let query = [("per_page".to_string(), "2".to_string())];
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let fetch = |range: PageRange, bodies: Vec<Value>| {
+        let (url, server) = serve_json(bodies);
+        let request = PageRequest {
+            method: "GET",
+            url: &url,
+            query: &query,
+            body: None,
+            stop: None,
+        };
+        let body = pagination::fetch_pages(&client, request, range, Some(&checkpoint), &mut |_| {})
+            .expect("fetch")
+            .body;
+        server.join().expect("server");
+        body
+    };
+
+    // Stopping after the first page leaves the next offset behind.
+    let first = fetch(
+        PageRange::parse("1:1").unwrap(),
+        vec![json!({"results": [1, 2], "count": 5})],
+    );
+    assert_eq!(first, json!([1, 2]));
+    assert_eq!(
+        checkpoint.load().unwrap(),
+        Some(pagination::Resume {
+            position: pagination::Position::Offset(2),
+            page: 1,
+        })
+    );
+
+    // The next run resumes at offset 2 and clears the file once done.
+    let rest = fetch(
+        PageRange::all(),
+        vec![
+            json!({"results": [3, 4], "count": 5}),
+            json!({"results": [5], "count": 5}),
+        ],
+    );
+    assert_eq!(rest, json!([3, 4, 5]));
+    assert!(!dir.join("cursor.json").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_headers_merge_by_profile_and_redact() {
+    let config = config::parse(
+        r#"
+[headers]
+"Accept-Language" = "en"
+"X-Tenant" = "acme"
+
+[profiles.staging.headers]
+"x-tenant" = "acme-staging"
+"X-Api-Token" = "s3cret"
+"#,
+    )
+    .expect("config");
+
+    assert_eq!(
+        config.headers("default"),
+        vec![
+            ("Accept-Language".to_string(), "en".to_string()),
+            ("X-Tenant".to_string(), "acme".to_string()),
+        ]
+    );
+    let mut headers = config.headers("staging");
+    assert_eq!(
+        headers[1],
+        ("X-Tenant".to_string(), "acme-staging".to_string())
+    );
+
+    let (name, value) = config::parse_header("Accept-Language: de").unwrap();
+    config::set_header(&mut headers, &name, &value);
+    assert_eq!(headers[0].1, "de");
+    assert!(config::parse_header("no colon").is_err());
+
+    let redacted = config::redact_headers(&headers);
+    assert_eq!(
+        redacted[2],
+        ("X-Api-Token".to_string(), "<redacted>".to_string())
+    );
+    assert!(config::parse("[unknown]\nx = 1").is_err());
+}
+
+#[test]
+fn query_values_are_coerced_by_type() {
+    let mut op = op_with_query(&["per_page", "archived", "search"]);
+    op.query[0].kind = Some("integer".to_string());
+    op.query[1].kind = Some("boolean".to_string());
+    let pair = |key: &str, value: &str| (key.to_string(), value.to_string());
+
+    let query = coerce_query(
+        &op,
+        vec![
+            pair("per_page", " 50"),
+            pair("archived", "yes"),
+            pair("search", "abc"),
+            pair("other", "x"),
+        ],
+        true,
+    )
+    .expect("valid");
+    assert_eq!(
+        query,
+        vec![
+            pair("per_page", "50"),
+            pair("archived", "true"),
+            pair("search", "abc"),
+            pair("other", "x"),
+        ]
+    );
+
+    let err = coerce_query(&op, vec![pair("per_page", "abc")], true).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "query param per_page=abc is not an integer"
+    );
+    let kept = coerce_query(&op, vec![pair("archived", "maybe")], false).expect("warning only");
+    assert_eq!(kept, vec![pair("archived", "maybe")]);
+}
+
+#[test]
+fn order_flags_build_order_by() {
+    let tree = command_tree::load_command_tree();
+    let query_for = |args: &[&str]| {
+        let mut argv = vec!["plane", "request", "GET", "x"];
+        argv.extend_from_slice(args);
+        let matches = build_cli(&tree, false)
+            .try_get_matches_from(argv)
+            .expect("parse");
+        let (_, sub) = matches.subcommand().expect("request");
+        build_query_params(sub, &[]).expect("query")
+    };
+    let pair = |value: &str| ("order_by".to_string(), value.to_string());
+
+    assert_eq!(
+        query_for(&["--order-by", "created_at"]),
+        vec![pair("created_at")]
+    );
+    assert_eq!(
+        query_for(&["--order-by", "created_at", "--order", "desc"]),
+        vec![pair("-created_at")]
+    );
+    assert_eq!(
+        query_for(&["--order-by", "-created_at", "--order", "asc"]),
+        vec![pair("created_at")]
+    );
+    assert_eq!(
+        query_for(&["--order-by", "name", "--query", "order_by=-priority"]),
+        vec![pair("-priority")]
+    );
+}
+
+#[test]
+fn batch_delete_reads_ids_and_relaxes_id_flag() {
+    assert_eq!(
+        batch::parse_ids("a\n  # skip\n\n b \n"),
+        vec!["a".to_string(), "b".to_string()]
+    );
+
+    let tree = command_tree::load_command_tree();
+    let delete = find_op(&tree, "project", "delete").expect("op");
+    assert_eq!(
+        batch::id_param(delete).map(|param| param.name.as_str()),
+        Some("pk")
+    );
+    assert!(batch::id_param(find_op(&tree, "project", "list").expect("op")).is_none());
+
+    let cli = || build_cli(&tree, false);
+    assert!(
+        cli()
+            .try_get_matches_from([
+                "plane", "project", "delete", "--slug", "w", "--batch", "ids.txt"
+            ])
+            .is_ok()
+    );
+    assert!(
+        cli()
+            .try_get_matches_from(["plane", "project", "delete", "--slug", "w"])
+            .is_err()
+    );
+}
+
+#[test]
+fn durations_parse_with_units() {
+    use std::time::Duration;
+    assert_eq!(http::parse_duration("30").unwrap(), Duration::from_secs(30));
+    assert_eq!(
+        http::parse_duration("1.5s").unwrap(),
+        Duration::from_millis(1500)
+    );
+    assert_eq!(
+        http::parse_duration("500ms").unwrap(),
+        Duration::from_millis(500)
+    );
+    assert_eq!(
+        http::parse_duration("2m").unwrap(),
+        Duration::from_secs(120)
+    );
+    assert!(http::parse_duration("soon").is_err());
+    assert!(http::parse_duration("-1").is_err());
+}
+
+#[test]
+fn http_version_flags() {
+    let tree = command_tree::load_command_tree();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "tree"];
+        argv.extend_from_slice(args);
+        build_cli(&tree, false).try_get_matches_from(argv)
+    };
+    assert_eq!(http_version(&parse(&[]).unwrap()), HttpVersion::Auto);
+    assert_eq!(
+        http_version(&parse(&["--http1"]).unwrap()),
+        HttpVersion::Http1
+    );
+    assert_eq!(
+        http_version(&parse(&["--http2-prior-knowledge"]).unwrap()),
+        HttpVersion::Http2PriorKnowledge
+    );
+    assert!(parse(&["--http1", "--http2-prior-knowledge"]).is_err());
+}
+
+#[test]
+fn base64_body_flags() {
+    let tree = command_tree::load_command_tree();
+    let path = std::env::temp_dir().join(format!("plane-b64-{}", std::process::id()));
+    fs::write(&path, b"\x89PNG").unwrap();
+    let path = path.to_string_lossy().to_string();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "tree"];
+        argv.extend_from_slice(args);
+        build_cli(&tree, false).try_get_matches_from(argv).unwrap()
+    };
+
+    let whole = read_body(&parse(&["--body-base64-file", &path])).unwrap();
+    assert_eq!(whole, Some(json!("iVBORw==")));
+    let field = read_body(&parse(&[
+        "--body-base64-file",
+        &path,
+        "--body-base64-field",
+        "avatar",
+    ]))
+    .unwrap();
+    assert_eq!(field, Some(json!({"avatar": "iVBORw=="})));
+    assert!(read_body(&parse(&["--body-base64-file", &path, "--body-json", "{}"])).is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn env_fallback_note_names_param_and_source() {
+    let param = Param {
+        name: "slug".to_string(),
+        flag: "workspace-slug".to_string(),
+    };
+    assert_eq!(
+        env_fallback_note(&param, "PLANE_WORKSPACE", "acme"),
+        "--workspace-slug not given; using PLANE_WORKSPACE=acme"
+    );
+}
+
+#[cfg(not(feature = "clipboard"))]
+#[test]
+fn clipboard_without_feature_explains_rebuild() {
+    let err = clipboard::copy("id").unwrap_err();
+    assert!(err.to_string().contains("--features clipboard"));
+}
+
+#[test]
+fn field_flags_build_nested_typed_body() {
+    let tree = command_tree::load_command_tree();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "tree"];
+        argv.extend_from_slice(args);
+        build_cli(&tree, false).try_get_matches_from(argv).unwrap()
+    };
+    let body = read_body(&parse(&[
+        "--field",
+        "name=Bug",
+        "--field",
+        "estimate=3",
+        "--field",
+        "archived=false",
+        "--field",
+        "assignee.id=u1",
+        "--field",
+        "parent=null",
+        "--field-json",
+        "labels=[\"a\"]",
+    ]))
+    .unwrap();
+    assert_eq!(
+        body,
+        Some(json!({
+            "name": "Bug",
+            "estimate": 3,
+            "archived": false,
+            "assignee": {"id": "u1"},
+            "parent": null,
+            "labels": ["a"],
+        }))
+    );
+    assert!(read_body(&parse(&["--field", "a=1", "--body-json", "{}"])).is_err());
+    assert!(read_body(&parse(&["--field", "a=1", "--field", "a.b=2"])).is_err());
+    assert!(read_body(&parse(&["--field", "novalue"])).is_err());
+}
+
+#[test]
+fn matched_op_note_uses_full_names() {
+    let tree = command_tree::load_command_tree();
+    let op = find_op(&tree, "proj", "arch").unwrap();
+    assert_eq!(
+        matched_op_note(resource_name(&tree, "proj"), op),
+        "matched project archive: POST workspaces/<str:slug>/projects/<uuid:project_id>/archive/"
+    );
+}
+
+#[test]
+fn trailing_slash_is_enforced_per_operation() {
+    let params = HashMap::from([("slug".to_string(), "acme".to_string())]);
+    let mut op = op_with_query(&[]);
+    assert_eq!(
+        build_path(&op, &params).unwrap(),
+        "workspaces/acme/projects/"
+    );
+
+    op.trailing_slash = Some(false);
+    assert_eq!(
+        build_path(&op, &params).unwrap(),
+        "workspaces/acme/projects"
+    );
+
+    op.path = "workspaces/<str:slug>/members".to_string();
+    assert_eq!(build_path(&op, &params).unwrap(), "workspaces/acme/members");
+    op.trailing_slash = Some(true);
+    assert_eq!(
+        build_path(&op, &params).unwrap(),
+        "workspaces/acme/members/"
+    );
+}
+
+#[test]
+fn explain_names_the_source_of_each_part() {
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "project",
+            "create",
+            "--slug",
+            "acme",
+            "--per-page",
+            "5",
+            "--query",
+            "foo=bar",
+            "--field",
+            "name=Web",
+            "--explain",
+        ])
+        .unwrap();
+    let (_, res_matches) = matches.subcommand().unwrap();
+    let (_, op_matches) = res_matches.subcommand().unwrap();
+    let op = find_op(&tree, "project", "create").unwrap();
+    let target = Target {
+        api_url: "https://api.plane.so",
+        base_path: "/api/v1",
+        method: &op.method,
+        url: "https://api.plane.so/api/v1/workspaces/acme/projects/",
+        accept: None,
+    };
+    let query = build_query_params(op_matches, &[]).unwrap();
+    let body = read_body(op_matches).unwrap();
+    let lines = explain::lines(op, op_matches, &target, &query, &[], body.as_ref()).unwrap();
+    assert!(lines.contains(&"  slug = acme  (--slug)".to_string()));
+    assert!(lines.contains(&"  foo = bar  (--query)".to_string()));
+    assert!(lines.contains(&"  per_page = 5  (--per-page)".to_string()));
+    assert!(lines.contains(&"body: (--field)".to_string()));
+}
+
+#[test]
+fn explain_names_body_template_and_vars() {
+    let path = env::temp_dir().join(format!("plane-explain-{}.j2", std::process::id()));
+    fs::write(
+        &path,
+        r#"{"name": "{{ name }}", "identifier": "{{ key }}"}"#,
+    )
+    .unwrap();
+    let path = path.to_string_lossy().to_string();
+    let tree = command_tree::load_command_tree();
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "project",
+            "create",
+            "--slug",
+            "acme",
+            "--body-template",
+            &path,
+            "--var",
+            "name=Web",
+            "--var",
+            "key=WEB",
+            "--explain",
+        ])
+        .unwrap();
+    let (_, res_matches) = matches.subcommand().unwrap();
+    let (_, op_matches) = res_matches.subcommand().unwrap();
+    let op = find_op(&tree, "project", "create").unwrap();
+    let target = Target {
+        api_url: "https://api.plane.so",
+        base_path: "/api/v1",
+        method: &op.method,
+        url: "https://api.plane.so/api/v1/workspaces/acme/projects/",
+        accept: None,
+    };
+    let body = read_body(op_matches).unwrap();
+    let lines = explain::lines(op, op_matches, &target, &[], &[], body.as_ref()).unwrap();
+    let expected = format!("body: (--body-template {path} with --var name, key)");
+    assert!(lines.contains(&expected), "{lines:?}");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn compact_output_wraps_only_long_containers() {
+    let value = json!({"results": [{"id": 1, "name": "A"}, {"id": 2, "name": "B"}], "count": 2});
+    assert_eq!(
+        output::render_compact(&value, 80),
+        r#"{"count": 2, "results": [{"id": 1, "name": "A"}, {"id": 2, "name": "B"}]}"#
+    );
+    assert_eq!(
+        output::render_compact(&value, 30),
+        "{\n  \"count\": 2,\n  \"results\": [\n    {\"id\": 1, \"name\": \"A\"},\n    {\"id\": 2, \"name\": \"B\"}\n  ]\n}"
+    );
+}
+
+#[test]
+fn request_body_positional() {
+    let tree = command_tree::load_command_tree();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "request", "POST", "x/"];
+        argv.extend_from_slice(args);
+        let matches = build_cli(&tree, false).try_get_matches_from(argv).unwrap();
+        request_body(matches.subcommand_matches("request").unwrap())
+    };
+    assert_eq!(parse(&[]).unwrap(), None);
+    assert_eq!(
+        parse(&[r#"{"name":"x"}"#]).unwrap(),
+        Some(json!({"name": "x"}))
+    );
+    let err = parse(&["{name"]).unwrap_err();
+    assert!(err.to_string().contains("not valid JSON"));
+    assert!(parse(&["{}", "--body-json", "{}"]).is_err());
+}
+
+#[test]
+fn no_body_forces_empty_and_rejects_body_flags() {
+    let tree = command_tree::load_command_tree();
+    let parse = |args: &[&str]| {
+        let mut argv = vec!["plane", "tree"];
+        argv.extend_from_slice(args);
+        build_cli(&tree, false).try_get_matches_from(argv).unwrap()
+    };
+    assert_eq!(read_body(&parse(&["--no-body"])).unwrap(), None);
+    assert!(read_body(&parse(&["--no-body", "--body-json", "{}"])).is_err());
+    assert!(read_body(&parse(&["--no-body", "--field", "a=1"])).is_err());
+}
+
+#[test]
+fn status_line_uses_canonical_reason() {
+    assert_eq!(status_line(201), "HTTP 201 Created");
+    assert_eq!(status_line(404), "HTTP 404 Not Found");
+    assert_eq!(status_line(599), "HTTP 599");
+}
+
+#[test]
+fn if_match_auto_reads_etag() {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/issue/", listener.local_addr().expect("addr"));
+    let server = std::thread::spawn(move || {
+        for etag in ["etag: \"v7\"\r\n", ""] {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let _ = stream.read(&mut [0u8; 4096]);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nconnection: close\r\n{etag}content-length: 2\r\n\r\n{{}}"
+            );
+            stream.write_all(response.as_bytes()).expect("write");
+        }
+    });
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    assert_eq!(fetch_etag(&client, &url).unwrap(), "\"v7\"");
+    let err = fetch_etag(&client, &url).unwrap_err();
+    assert!(err.to_string().contains("no ETag"));
+    server.join().expect("server");
+}
+
+#[test]
+fn select_projects_rows_locally() {
+    let fields = vec!["id".to_string(), "state.name".to_string()];
+    let row = json!({"id": 1, "name": "A", "state": {"name": "Todo", "group": "backlog"}});
+    assert_eq!(
+        output::select(&row, &fields),
+        json!({"id": 1, "state": {"name": "Todo"}})
+    );
+    let envelope = json!({"results": [row, {"id": 2}], "next_cursor": "1:1:0"});
+    assert_eq!(
+        output::select(&envelope, &fields),
+        json!({"results": [{"id": 1, "state": {"name": "Todo"}}, {"id": 2}], "next_cursor": "1:1:0"})
+    );
+}
+
+#[test]
+fn export_covers_workspace_and_project_lists_only() {
+    let tree = command_tree::load_command_tree();
+    let ops = export::export_ops(&tree);
+    let files: Vec<&str> = ops.iter().map(|export| export.file.as_str()).collect();
+    assert!(files.contains(&"project"));
+    assert!(files.contains(&"work-item"));
+    assert!(files.contains(&"cycle-archived-cycles-list"));
+    // Needs an issue id, and `users/me/` is not workspace data.
+    assert!(!files.contains(&"work-item-comments-list"));
+    assert!(!files.iter().any(|file| file.starts_with("user")));
+    let work_items = ops
+        .iter()
+        .find(|export| export.file == "work-item")
+        .unwrap();
+    assert!(work_items.per_project);
+    let stickies = ops.iter().find(|export| export.file == "sticky").unwrap();
+    assert!(!stickies.per_project);
+}
+
+#[test]
+fn body_template_renders_vars_and_rejects_undefined() {
+    let vars = BTreeMap::from([
+        ("title".to_string(), "Bug".to_string()),
+        ("labels".to_string(), "a,b".to_string()),
+    ]);
+    let template = r#"{"name": "{{ title }}", "priority": "{{ priority | default('none') }}",
+        "labels": [{% for label in labels | split(",") %}"{{ label }}"{% if not loop.last %}, {% endif %}{% endfor %}]}"#;
+    assert_eq!(
+        render_body_template(template, &vars).unwrap(),
+        json!({"name": "Bug", "priority": "none", "labels": ["a", "b"]})
+    );
+    assert!(render_body_template(r#"{"name": "{{ missing }}"}"#, &vars).is_err());
+    let err = render_body_template("{{ title }}", &vars).unwrap_err();
+    assert!(err.to_string().contains("valid JSON"));
+}
+
+#[test]
+fn interrupted_error_is_recognizable() {
+    let err: anyhow::Error = interrupt::Interrupted.into();
+    assert!(err.is::<interrupt::Interrupted>());
+    assert_eq!(err.to_string(), "interrupted; output is partial");
+    assert!(!interrupt::requested());
+    assert!(interrupt::check().is_ok());
+}
+
+#[test]
+fn max_response_size_rejects_large_bodies() {
+    assert_eq!(http::parse_size("1048576").unwrap(), 1 << 20);
+    assert_eq!(http::parse_size("64mb").unwrap(), 64 << 20);
+    assert_eq!(http::parse_size("1GB").unwrap(), 1 << 30);
+    assert!(http::parse_size("lots").is_err());
+
+    let (url, server) = serve_json(vec![
+        json!({"name": "x".repeat(100)}),
+        json!({"name": "ok"}),
+    ]);
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_max_response_size(Some(64));
+    let err = client.execute("GET", &url, &[], None).unwrap_err();
+    assert!(err.to_string().contains("--max-response-size"));
+    let ok = client.execute("GET", &url, &[], None).unwrap();
+    assert_eq!(ok.body, json!({"name": "ok"}));
+    server.join().expect("server");
+}
+
+#[test]
+fn ops_by_path_groups_methods() {
+    let tree = command_tree::load_command_tree();
+    let paths = ops_by_path(&tree, false);
+    let projects = &paths["workspaces/<str:slug>/projects/"];
+    let methods: Vec<&str> = projects.iter().map(|(_, op)| op.method.as_str()).collect();
+    assert_eq!(methods, ["GET", "POST"]);
+    let total: usize = paths.values().map(Vec::len).sum();
+    let visible = tree
+        .resources
+        .iter()
+        .flat_map(|res| &res.ops)
+        .filter(|op| !op.deprecated)
+        .count();
+    assert_eq!(total, visible);
+}
+
+#[test]
+fn flat_columns_expand_nested_leaves() {
+    let body = json!([
+        {"id": "1", "state": {"name": "Todo"}, "labels": [{"name": "bug"}]},
+        {"id": "2", "state": {"name": "Done"}, "labels": [], "meta": {"a": {"b": {"c": 1}}}}
+    ]);
+    let rows = output::rows(&body);
+    let paths: Vec<String> = output::flat_columns(&rows, 2)
+        .into_iter()
+        .map(|col| col.path)
+        .collect();
+    assert_eq!(paths, ["id", "labels.0", "state.name", "meta.a"]);
+    assert_eq!(output::cell(rows[0], "labels.0"), r#"{"name":"bug"}"#);
+    let deep: Vec<String> = output::flat_columns(&rows, 3)
+        .into_iter()
+        .map(|col| col.path)
+        .collect();
+    assert!(deep.contains(&"labels.0.name".to_string()));
+}
+
+#[test]
+fn api_version_filters_operations() {
+    let mut tree = command_tree::load_command_tree();
+    let ops = &mut tree.resources[0].ops;
+    ops.truncate(2);
+    ops[0].min_api_version = Some("1.2".to_string());
+    ops[1].max_api_version = Some("v1.0".to_string());
+    let (newer, older) = (ops[0].name.clone(), ops[1].name.clone());
+
+    let mut old_tree = tree.clone();
+    old_tree.retain_api_version(&command_tree::parse_version("1.0.0").unwrap());
+    let names: Vec<&str> = old_tree.resources[0]
+        .ops
+        .iter()
+        .map(|op| op.name.as_str())
+        .collect();
+    assert_eq!(names, [older.as_str()]);
+
+    tree.retain_api_version(&command_tree::parse_version("1.2").unwrap());
+    let names: Vec<&str> = tree.resources[0]
+        .ops
+        .iter()
+        .map(|op| op.name.as_str())
+        .collect();
+    assert_eq!(names, [newer.as_str()]);
+    assert!(command_tree::parse_version("1.x").is_none());
+    assert_eq!(
+        api_version_arg(&["plane".into(), "--api-version=0.24".into()]),
+        Some("0.24".to_string())
+    );
+}
+
+#[test]
+fn query_file_lines_parse_with_line_numbers() {
+    let pairs = parse_query_lines("# open bugs\nstate=open\n\n  labels=bug,p1 \n").unwrap();
+    assert_eq!(
+        pairs,
+        [
+            ("state".to_string(), "open".to_string()),
+            ("labels".to_string(), "bug,p1".to_string())
+        ]
+    );
+    let err = parse_query_lines("state=open\nbroken\n").unwrap_err();
+    assert!(format!("{err:#}").starts_with("line 2: "), "{err:#}");
+}
+
+#[test]
+fn metrics_render_prometheus_text() {
+    let snapshot = metrics::Snapshot {
+        requests: 4,
+        retries: 1,
+        bytes: 2048,
+        statuses: BTreeMap::from([(200, 3), (503, 1)]),
+        duration: Duration::from_millis(1500),
+        success: true,
+    };
+    let text = metrics::render(&snapshot);
+    assert!(text.contains("# TYPE plane_requests_total counter\nplane_requests_total 4\n"));
+    assert!(text.contains("plane_responses_total{status=\"503\"} 1\n"));
+    assert!(text.contains("plane_run_duration_seconds 1.500\n"));
+    assert!(text.ends_with("plane_run_success 1\n"));
+}
+
+#[test]
+fn stream_body_uploads_file_with_content_type() {
+    use std::io::{Read, Write};
+    let path = std::env::temp_dir().join(format!("plane-stream-{}.ndjson", std::process::id()));
+    fs::write(&path, "{\"a\":1}\n".repeat(5000)).expect("write");
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/import/", listener.local_addr().expect("addr"));
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 8192];
+        // Read until the whole body announced by content-length has arrived.
+        loop {
+            let n = stream.read(&mut buf).expect("read");
+            raw.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&raw).to_lowercase();
+            if let Some(end) = text.find("\r\n\r\n")
+                && let Some(length) = text
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .and_then(|length| length.trim().parse::<usize>().ok())
+                && raw.len() >= end + 4 + length
+            {
+                let reply = format!("{{\"received\":{length}}}");
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{reply}",
+                    reply.len()
+                );
+                stream.write_all(response.as_bytes()).expect("write");
+                return text.contains("content-type: application/x-ndjson");
+            }
+            assert!(n > 0, "connection closed early");
+        }
+    });
+    let client = HttpClient::new("test".to_string(), ClientOptions::default()).expect("client");
+    let body = stream_source(path.to_str().unwrap()).expect("open");
+    let response = client
+        .send_stream("POST", &url, &[], body, "application/x-ndjson")
+        .expect("upload");
+    assert!(server.join().expect("server"));
+    fs::remove_file(&path).ok();
+    assert_eq!(response.body, json!({"received": 8 * 5000}));
+}
+
+#[test]
+fn raw_scalar_unquotes_scalars_only() {
+    assert_eq!(output::raw_scalar(&json!("a b")).as_deref(), Some("a b"));
+    assert_eq!(output::raw_scalar(&json!(true)).as_deref(), Some("true"));
+    assert_eq!(output::raw_scalar(&json!(1.5)).as_deref(), Some("1.5"));
+    assert_eq!(output::raw_scalar(&Value::Null).as_deref(), Some(""));
+    assert_eq!(output::raw_scalar(&json!({"a": 1})), None);
+}
+
+#[test]
+fn each_retry_attempt_gets_a_fresh_timeout() {
+    use std::io::{Read, Write};
+    use std::time::Duration;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let server = std::thread::spawn(move || {
+        // Slow: hold the first attempt past its timeout without answering.
+        let (slow, _) = listener.accept().expect("first accept");
+        let (mut stream, _) = listener.accept().expect("second accept");
+        drop(slow);
+        let _ = stream.read(&mut [0u8; 4096]);
+        // Fast, but only after most of a timeout: fails if the attempt shared a budget.
+        std::thread::sleep(Duration::from_millis(250));
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 11\r\n\r\n{\"ok\":true}")
+            .expect("write");
+    });
+
+    let options = ClientOptions {
+        timeout: Some(Duration::from_millis(400)),
+        ..ClientOptions::default()
+    };
+    // The first attempt plus 500ms backoff uses ~900ms of the 1s budget; the second
+    // attempt still gets its own 400ms rather than the ~100ms left.
+    let client = HttpClient::new("test".to_string(), options)
+        .expect("client")
+        .with_retries(1)
+        .with_retry_timeout(Some(Duration::from_secs(1)));
+    let response = client
+        .execute("GET", &format!("http://{addr}/"), &[], None)
+        .expect("second attempt succeeds");
+    server.join().expect("server");
+    assert_eq!(response.body, serde_json::json!({"ok": true}));
+}
+
+#[test]
+fn build_path_handles_angle_and_colon_tokens() {
+    let mut op = op_with_query(&[]);
+    op.path = "workspaces/<str:slug>/projects/:project_id/issues/<issue_id>/".to_string();
+    let params = HashMap::from([
+        ("slug".to_string(), "acme".to_string()),
+        ("project_id".to_string(), "p1".to_string()),
+        ("issue_id".to_string(), "i9".to_string()),
+    ]);
+    assert_eq!(
+        build_path(&op, &params).unwrap(),
+        "workspaces/acme/projects/p1/issues/i9/"
+    );
+    op.path = ":slug/members".to_string();
+    assert_eq!(build_path(&op, &params).unwrap(), "acme/members");
+    // A colon inside a segment is literal, and the name follows the last colon in brackets.
+    op.path = "a:b/<x:y:slug>".to_string();
+    assert_eq!(build_path(&op, &params).unwrap(), "a:b/acme");
+    op.path = "bad/<slug".to_string();
+    assert!(build_path(&op, &params).is_err());
+}
+
+#[test]
+fn plane_client_executes_operations_by_name() {
+    let (url, server) = serve_json(vec![json!({"results": [{"id": "p1"}]})]);
+    let client = plane::PlaneClient::new(url.trim_end_matches('/'), "test").expect("client");
+    if std::env::var_os("PLANE_WORKSPACE").is_none() {
+        let err = client
+            .execute("project", "list", &HashMap::new(), &[], None)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "missing required argument --slug");
+    }
+    // Left-out params fall back like the CLI's --workspace.
+    let scoped = plane::PlaneClient::new("https://plane.example.com", "test")
+        .expect("client")
+        .with_workspace("beta");
+    assert!(
+        scoped
+            .url("project", "list", &HashMap::new())
+            .unwrap()
+            .ends_with("/api/v1/workspaces/beta/projects/")
+    );
+
+    let params = HashMap::from([("slug".to_string(), "acme".to_string())]);
+    assert!(
+        client
+            .url("proj", "list", &params)
+            .unwrap()
+            .ends_with("/api/v1/workspaces/acme/projects/")
+    );
+    let response = client
+        .execute("project", "list", &params, &[], None)
+        .expect("execute");
+    server.join().expect("server");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["results"][0]["id"], "p1");
+}
+
+#[test]
+fn exec_document_resolves_like_flags() {
+    let tree = command_tree::load_command_tree();
+    let doc = json!({
+        "resource": "proj",
+        "op": "list",
+        "path_params": {"slug": "acme"},
+        "query": {"per_page": 5, "expand": ["state", "labels"]},
+    });
+    let request = exec::parse(&tree, doc, false, None).unwrap();
+    assert_eq!(request.op.path, "workspaces/<str:slug>/projects/");
+    assert_eq!(request.params["slug"], "acme");
+    assert_eq!(
+        request.query,
+        [
+            ("expand".to_string(), "state".to_string()),
+            ("expand".to_string(), "labels".to_string()),
+            ("per_page".to_string(), "5".to_string()),
+        ]
+    );
+    assert!(request.body.is_none());
+
+    let missing = json!({"resource": "project", "op": "list"});
+    if std::env::var_os("PLANE_WORKSPACE").is_none() {
+        let err = exec::parse(&tree, missing, false, None).unwrap_err();
+        assert_eq!(err.to_string(), "missing required argument --slug");
+    }
+    let unknown = json!({"resource": "project", "op": "list", "params": {}});
+    assert!(exec::parse(&tree, unknown, false, None).is_err());
+}
+
+#[test]
+fn min_interval_spaces_consecutive_requests() {
+    let (url, server) = serve_json(vec![json!({}), json!({}), json!({})]);
+    let client = HttpClient::new("test".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_pacer(Some(http::Pacer::new(Duration::from_millis(150))));
+    let started = std::time::Instant::now();
+    for _ in 0..3 {
+        client.execute("GET", &url, &[], None).expect("request");
+    }
+    server.join().expect("server");
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn shared_pacer_spaces_requests_across_workers() {
+    let (url, server) = serve_json(vec![json!({}), json!({}), json!({}), json!({})]);
+    let pacer = http::Pacer::new(Duration::from_millis(100));
+    let started = std::time::Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                let client = HttpClient::new("test".to_string(), ClientOptions::default())
+                    .expect("client")
+                    .with_pacer(Some(pacer.clone()));
+                for _ in 0..2 {
+                    client.execute("GET", &url, &[], None).expect("request");
+                }
+            });
+        }
+    });
+    server.join().expect("server");
+    // Four requests on one schedule; separate pacers would finish after ~100ms.
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn print_url_encodes_the_query() {
+    let query = vec![
+        ("per_page".to_string(), "5".to_string()),
+        ("search".to_string(), "login bug&more".to_string()),
+    ];
+    assert_eq!(
+        resolved_url(
+            "https://api.plane.so/api/v1/workspaces/acme/projects/",
+            &query
+        )
+        .unwrap(),
+        "https://api.plane.so/api/v1/workspaces/acme/projects/?per_page=5&search=login+bug%26more"
+    );
+    assert_eq!(
+        resolved_url("https://api.plane.so/api/v1/users/me/", &[]).unwrap(),
+        "https://api.plane.so/api/v1/users/me/"
+    );
+    assert!(resolved_url("not a url", &[]).is_err());
+}
+
+#[test]
+fn non_json_default_accept_adds_download() {
+    let mut tree = command_tree::load_command_tree();
+    let res = tree.resources[0].name.clone();
+    tree.resources[0].ops[0].default_accept = Some("text/csv".to_string());
+    tree.resources[0].ops[1].default_accept = Some("application/vnd.api+json".to_string());
+    let (csv, json_op) = (
+        tree.resources[0].ops[0].name.clone(),
+        tree.resources[0].ops[1].name.clone(),
+    );
+    let cli = build_cli(&tree, false);
+    let res_cmd = find_subcommand(&cli, &res).expect("resource");
+    let has_download = |op: &str| {
+        find_subcommand(res_cmd, op)
+            .expect("op")
+            .get_arguments()
+            .any(|arg| arg.get_id() == "download")
+    };
+    assert!(has_download(&csv));
+    assert!(!has_download(&json_op));
+}
+
+#[test]
+fn require_results_detects_empty_bodies() {
+    assert!(output::is_empty_result(&Value::Null));
+    assert!(output::is_empty_result(&json!([])));
+    assert!(output::is_empty_result(
+        &json!({"results": [], "next_cursor": null})
+    ));
+    assert!(!output::is_empty_result(&json!([{"id": 1}])));
+    assert!(!output::is_empty_result(&json!({"id": 1})));
+    assert!(!output::is_empty_result(&json!(false)));
+}
+
+#[test]
+fn config_profiles_inherit_and_reject_cycles() {
+    let config = config::parse(
+        r#"
+[headers]
+"X-Team" = "core"
+
+[profiles.base]
+base_url = "https://plane.example.com/api/v1"
+headers = { "X-Tenant" = "acme", "X-Trace" = "on" }
+
+[profiles.prod]
+inherits = "base"
+
+[profiles.staging]
+inherits = "prod"
+base_url = "https://staging.example.com/api/v1"
+headers = { "x-tenant" = "acme-staging" }
+"#,
+    )
+    .expect("config");
+    let staging = config.resolve("staging");
+    assert_eq!(staging.chain, ["staging", "prod", "base"]);
+    assert_eq!(
+        staging.base_url.as_deref(),
+        Some("https://staging.example.com/api/v1")
+    );
+    assert_eq!(
+        staging.headers,
+        [
+            ("X-Team".to_string(), "core".to_string()),
+            ("X-Tenant".to_string(), "acme-staging".to_string()),
+            ("X-Trace".to_string(), "on".to_string()),
+        ]
+    );
+    assert_eq!(
+        config.resolve("prod").base_url.as_deref(),
+        Some("https://plane.example.com/api/v1")
+    );
+    assert_eq!(config.chain("work"), ["work"]);
+
+    let cycle = config::parse("[profiles.a]\ninherits = \"b\"\n[profiles.b]\ninherits = \"a\"\n");
+    assert!(
+        cycle
+            .unwrap_err()
+            .to_string()
+            .contains("cycle: a -> b -> a")
+    );
+    let unknown = config::parse("[profiles.a]\ninherits = \"nope\"\n");
+    assert!(
+        unknown
+            .unwrap_err()
+            .to_string()
+            .contains("unknown profile nope")
+    );
+}
+
+#[test]
+fn header_file_lines_parse_with_line_numbers() {
+    let headers =
+        config::parse_header_lines("# tracing\nX-Trace: on\n\nX-Tenant:  acme \n").unwrap();
+    assert_eq!(
+        headers,
+        [
+            ("X-Trace".to_string(), "on".to_string()),
+            ("X-Tenant".to_string(), "acme".to_string())
+        ]
+    );
+    let err = config::parse_header_lines("X-Trace: on\nno colon here\n").unwrap_err();
+    assert!(
+        format!("{err:#}").starts_with("line 2: invalid header"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn page_totals_come_from_total_count_or_offset_count() {
+    assert_eq!(
+        pagination::total_count(
+            &json!({"results": [], "next_cursor": "1:1:0", "total_count": 1200, "count": 100})
+        ),
+        Some(1200)
+    );
+    // On cursor envelopes `count` is the page size, not the total.
+    assert_eq!(
+        pagination::total_count(&json!({"results": [], "next_cursor": "1:1:0", "count": 100})),
+        None
+    );
+    assert_eq!(
+        pagination::total_count(&json!({"results": [], "count": 250, "next": null})),
+        Some(250)
+    );
+}
+
+#[test]
+fn field_presets_expand_in_place() {
+    let config = config::parse("[fields]\nsummary = \"id, name,state\"\n").unwrap();
+    assert_eq!(
+        expand_fields(
+            &["sequence_id", "@summary", "name", "custom.x"],
+            &config.fields
+        )
+        .unwrap(),
+        ["sequence_id", "id", "name", "state", "custom.x"]
+    );
+    let err = expand_fields(&["@full"], &config.fields).unwrap_err();
+    assert!(err.to_string().contains("unknown field preset @full"));
+}
+
+#[test]
+fn request_methods_are_case_insensitive() {
+    for method in ["get", "Get", "GET"] {
+        assert_eq!(http::parse_method(method).unwrap(), reqwest::Method::GET);
+        assert_eq!(http::normalize_method(method), "GET");
+    }
+    assert_eq!(http::normalize_method("patch"), "PATCH");
+    // Extension methods pass through untouched.
+    assert_eq!(http::parse_method("PROPFIND").unwrap().as_str(), "PROPFIND");
+    assert_eq!(http::normalize_method("purge"), "purge");
+    assert!(http::parse_method("BAD METHOD").is_err());
+}
+
+#[test]
+fn trace_envelope_captures_request_and_response() {
+    let (url, server) = serve_json(vec![json!({"id": 1})]);
+    let client = HttpClient::new("secret".to_string(), ClientOptions::default())
+        .expect("client")
+        .with_header("X-Trace", "on");
+    let query = vec![("expand".to_string(), "state".to_string())];
+    let headers = client
+        .request_headers("POST", &url, &query, Some(&json!({"name": "x"})))
+        .expect("headers");
+    let redacted = config::redact_headers(&headers);
+    assert!(redacted.contains(&("x-api-key".to_string(), "<redacted>".to_string())));
+    assert!(redacted.contains(&("x-trace".to_string(), "on".to_string())));
+    assert!(redacted.contains(&("content-type".to_string(), "application/json".to_string())));
+
+    let response = client.execute("GET", &url, &[], None).expect("execute");
+    server.join().expect("server");
+    let request = json!({"method": "GET", "url": url});
+    let envelope = trace_envelope(request, response, Duration::from_millis(12));
+    assert_eq!(envelope["request"]["method"], "GET");
+    assert_eq!(envelope["response"]["status"], 200);
+    assert_eq!(envelope["response"]["body"], json!({"id": 1}));
+    assert_eq!(
+        envelope["response"]["headers"]["content-type"],
+        "application/json"
+    );
+    assert_eq!(envelope["timing_ms"], 12);
+}
+
+#[test]
+fn expand_values_dedupe_and_check_against_op() {
+    let tree = command_tree::load_command_tree();
+    let parse = |strict: &str| {
+        build_cli(&tree, false)
+            .try_get_matches_from([
+                "plane",
+                strict,
+                "--expand",
+                "state,labels",
+                "--expand",
+                "state",
+                "--expand",
+                "assignees,labels",
+                "tree",
+            ])
+            .expect("parse")
+    };
+    let matches = parse("--verbose");
+    let query = build_query_params(&matches, &[]).expect("query");
+    let expands: Vec<&str> = query
+        .iter()
+        .filter(|(key, _)| key == "expand")
+        .map(|(_, value)| value.as_str())
+        .collect();
+    assert_eq!(expands, ["state,labels", "assignees"]);
+
+    let strict = parse("--strict-query");
+    let mut op = op_with_query(&[]);
+    // Unmodeled ops pass everything through.
+    check_expand_keys(&op, &strict).expect("unmodeled");
+    op.expand = vec!["state".to_string(), "labels".to_string()];
+    check_expand_keys(&op, &matches).expect("warns only");
+    let err = check_expand_keys(&op, &strict).unwrap_err();
+    assert!(err.to_string().contains("unknown expand assignees"));
+}
+
+#[test]
+fn workspace_flag_fills_workspace_params() {
+    let tree = command_tree::load_command_tree();
+    let op = find_op(&tree, "project", "list").expect("op");
+    let matches = build_cli(&tree, false)
+        .try_get_matches_from(["plane", "project", "list", "--workspace", "acme"])
+        .expect("parse");
+    let (_, op_matches) = matches.subcommand().expect("resource");
+    let (_, op_matches) = op_matches.subcommand().expect("op");
+    let params = collect_path_params(op, op_matches).expect("params");
+    assert_eq!(params["slug"], "acme");
+
+    // The flag wins over PLANE_WORKSPACE; an explicit path param wins over both.
+    let slug = &op.params[0];
+    assert_eq!(
+        param_fallback(slug, Some("acme")),
+        Some(("--workspace", "acme".to_string()))
+    );
+    let explicit = build_cli(&tree, false)
+        .try_get_matches_from([
+            "plane",
+            "project",
+            "list",
+            "--workspace",
+            "acme",
+            "--slug",
+            "beta",
+        ])
+        .expect("parse");
+    let (_, op_matches) = explicit.subcommand().expect("resource");
+    let (_, op_matches) = op_matches.subcommand().expect("op");
+    assert_eq!(
+        collect_path_params(op, op_matches).expect("params")["slug"],
+        "beta"
+    );
+
+    let request = exec::parse(
+        &tree,
+        json!({"resource": "project", "op": "list"}),
+        false,
+        Some("acme"),
+    )
+    .expect("exec");
+    assert_eq!(request.params["slug"], "acme");
+}
+
+#[test]
+fn api_base_reports_its_sources() {
+    let tree = command_tree::load_command_tree();
+    let base = resolve_api_base_env(&tree, None, |_| None).expect("defaults");
+    assert_eq!(
+        base.to_string(),
+        "api_url https://api.plane.so (from default), base_path /api/v1 (from command tree default)"
+    );
+    let base = resolve_api_base_env(&tree, Some("https://plane.example.com/api/v2"), |_| None)
+        .expect("profile");
+    assert_eq!(
+        base.to_string(),
+        "api_url https://plane.example.com (from profile base_url), base_path /api/v2 (from profile base_url)"
+    );
+}
+
+#[test]
+fn api_env_vars_override_only_their_part_of_the_profile_base_url() {
+    let tree = command_tree::load_command_tree();
+    let profile = Some("https://plane.example.com/api/v2");
+    let env = |name: &'static str, value: &'static str| {
+        move |var: &str| (var == name).then(|| value.to_string())
+    };
+    let base = resolve_api_base_env(&tree, profile, env("PLANE_API_BASE_PATH", "/api/v1"))
+        .expect("base path only");
+    assert_eq!(
+        base.to_string(),
+        "api_url https://plane.example.com (from profile base_url), base_path /api/v1 (from PLANE_API_BASE_PATH)"
+    );
+    let base = resolve_api_base_env(
+        &tree,
+        profile,
+        env("PLANE_API_URL", "https://other.example.com"),
+    )
+    .expect("api url only");
+    assert_eq!(
+        base.to_string(),
+        "api_url https://other.example.com (from PLANE_API_URL), base_path /api/v2 (from profile base_url)"
+    );
+}